@@ -0,0 +1,138 @@
+//! Timing the phases of a solution over repeated runs, to compare alternative algorithms.
+
+use crate::json::Json;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The wall time of a phase over all runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timings {
+    /// The timings of the given samples, `None` if there are none.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort();
+        let min = *samples.first()?;
+        let max = *samples.last()?;
+        // The mean of the two middle samples if their number is even
+        let count = samples.len();
+        let median = (samples[(count - 1) / 2] + samples[count / 2]) / 2;
+        Some(Timings { min, median, max })
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("min_seconds", Json::from(self.min.as_secs_f64())),
+            ("median_seconds", Json::from(self.median.as_secs_f64())),
+            ("max_seconds", Json::from(self.max.as_secs_f64())),
+        ])
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min {:.1?}, median {:.1?}, max {:.1?}",
+            self.min, self.median, self.max
+        )
+    }
+}
+
+/// Run `phase` `runs` times (at least once), and return its last result with the timings.
+///
+/// Stops at the first error, as the following runs would fail the same way.
+pub fn measure<T, E, F>(runs: usize, mut phase: F) -> Result<(T, Timings), E>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    let mut samples = Vec::with_capacity(runs);
+    loop {
+        let start = Instant::now();
+        let result = phase()?;
+        samples.push(start.elapsed());
+
+        if samples.len() >= runs {
+            let timings = Timings::from_samples(samples).expect("at least one run");
+            return Ok((result, timings));
+        }
+    }
+}
+
+/// The timings of each phase of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bench {
+    pub runs: usize,
+    pub parse: Timings,
+    /// `None` if part 1 was not solved
+    pub part1: Option<Timings>,
+    /// `None` if part 2 was not solved
+    pub part2: Option<Timings>,
+}
+
+impl Bench {
+    /// The phases which were run, with their names.
+    pub fn phases(&self) -> Vec<(&'static str, Timings)> {
+        let mut phases = vec![("parse", self.parse)];
+        phases.extend(self.part1.map(|timings| ("part 1", timings)));
+        phases.extend(self.part2.map(|timings| ("part 2", timings)));
+        phases
+    }
+
+    pub fn to_json(&self) -> Json {
+        let part = |timings: Option<Timings>| {
+            timings
+                .map(|timings| timings.to_json())
+                .unwrap_or(Json::Null)
+        };
+        Json::object(vec![
+            ("runs", Json::from(self.runs)),
+            ("parse", self.parse.to_json()),
+            ("part1", part(self.part1)),
+            ("part2", part(self.part2)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().cloned().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_timings() {
+        let odd = Timings::from_samples(millis(&[5, 1, 3])).unwrap();
+        let even = Timings::from_samples(millis(&[4, 1, 2, 8])).unwrap();
+
+        assert_eq!(Duration::from_millis(1), odd.min);
+        assert_eq!(Duration::from_millis(3), odd.median);
+        assert_eq!(Duration::from_millis(5), odd.max);
+        assert_eq!(Duration::from_millis(3), even.median);
+        assert_eq!(None, Timings::from_samples(Vec::new()));
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let (result, _) = measure(3, || {
+            calls += 1;
+            Ok::<_, ()>(calls)
+        })
+        .unwrap();
+        assert_eq!(3, result);
+
+        let mut calls = 0;
+        let error = measure(0, || {
+            calls += 1;
+            Err::<(), _>(calls)
+        });
+        assert_eq!(Err(1), error);
+    }
+}
@@ -0,0 +1,357 @@
+//! Property tests without dependencies: check a property for many generated values,
+//! and shrink a failing value to a simple one before reporting it.
+//!
+//! Values are generated from a sequence of random choices. A failing value is shrunk by
+//! replaying simpler sequences, with fewer and smaller choices, as long as the property
+//! still fails. Every choice shrinks towards the lowest value it can take.
+
+use crate::random::{scale, scale_range, Rng};
+use std::cell::Cell;
+use std::env;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Number of values checked for each property
+pub const CASES: u64 = 256;
+
+/// Seed of the first case, unless the environment variable `AOC_CHECK_SEED` gives another
+pub const DEFAULT_SEED: u64 = 2018;
+
+/// Maximal number of values tried while shrinking a failing value
+const SHRINK_ATTEMPTS: usize = 10_000;
+
+/// The source of the random choices a value is generated from.
+///
+/// Provides the methods of `Rng`, and collections made of random items.
+#[derive(Debug)]
+pub struct Choices {
+    rng: Rng,
+    /// The choices to make again while shrinking, further choices are 0
+    replay: Option<Vec<u64>>,
+    made: Vec<u64>,
+}
+
+impl Choices {
+    fn random(seed: u64) -> Self {
+        Choices {
+            rng: Rng::new(seed),
+            replay: None,
+            made: Vec::new(),
+        }
+    }
+
+    fn replay(choices: Vec<u64>) -> Self {
+        Choices {
+            rng: Rng::new(0),
+            replay: Some(choices),
+            made: Vec::new(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let choice = match &self.replay {
+            Some(choices) => choices.get(self.made.len()).cloned().unwrap_or(0),
+            None => self.rng.next_u64(),
+        };
+        self.made.push(choice);
+        choice
+    }
+
+    /// A number in `0..bound`.
+    ///
+    /// Panics if `bound` is 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        scale(self.next_u64(), bound)
+    }
+
+    /// A number in the inclusive `range`, shrinking towards its start.
+    ///
+    /// Panics if the range is empty.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        scale_range(self.next_u64(), range)
+    }
+
+    /// True with the probability `numerator / denominator`, shrinking towards true.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    /// A random item of `items`, shrinking towards the first.
+    ///
+    /// Panics if `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// Shuffle `items` in place, every order is equally likely.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// Items made by `item`, as many as in `lengths`, shrinking towards the shortest.
+    ///
+    /// Before each optional item it is chosen whether to continue,
+    /// so dropping an item while shrinking leaves the others unchanged.
+    pub fn vec<T, F>(&mut self, lengths: RangeInclusive<usize>, mut item: F) -> Vec<T>
+    where
+        F: FnMut(&mut Choices) -> T,
+    {
+        let (min, max) = lengths.into_inner();
+        let average = max.saturating_sub(min).div_ceil(2);
+        let mut items = Vec::with_capacity(min);
+        while items.len() < min || (items.len() < max && self.below(average + 1) != 0) {
+            items.push(item(self));
+        }
+        items
+    }
+
+    /// Characters of `alphabet`, as many as in `lengths`, shrinking towards its first character.
+    ///
+    /// Panics if `alphabet` is empty.
+    pub fn string(&mut self, lengths: RangeInclusive<usize>, alphabet: &str) -> String {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        self.vec(lengths, |choices| *choices.choose(&alphabet))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// A value for which a property does not hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample<T> {
+    /// The seed of the case which failed first
+    pub seed: u64,
+    /// The shrunk value
+    pub value: T,
+    /// Why the property does not hold for the shrunk value
+    pub message: String,
+    /// Number of times a simpler failing value was found
+    pub shrinks: usize,
+}
+
+impl<T: fmt::Debug> fmt::Display for Counterexample<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Property does not hold for the case with seed {} (shrunk {} times): {}\n{:#?}",
+            self.seed, self.shrinks, self.message, self.value
+        )
+    }
+}
+
+/// Check `property` for `CASES` values made by `generate`.
+///
+/// Panics with the simplest failing value found, and the seed to reproduce it with
+/// `AOC_CHECK_SEED`.
+pub fn check<T, G, P>(generate: G, property: P)
+where
+    T: fmt::Debug,
+    G: Fn(&mut Choices) -> T,
+    P: Fn(&T) -> Result<(), String>,
+{
+    if let Some(counterexample) = falsify(seed(), CASES, generate, property) {
+        panic!("{}", counterexample);
+    }
+}
+
+/// Check that every value made by `generate` is parsed again from its displayed form.
+pub fn round_trip<T, G>(generate: G)
+where
+    T: FromStr + fmt::Display + PartialEq + fmt::Debug,
+    T::Err: fmt::Debug,
+    G: Fn(&mut Choices) -> T,
+{
+    check(generate, |value| {
+        let text = value.to_string();
+        match text.parse::<T>() {
+            Ok(ref parsed) if parsed == value => Ok(()),
+            Ok(parsed) => Err(format!("'{}' is parsed as {:?}", text, parsed)),
+            Err(error) => Err(format!("'{}' is not parsed: {:?}", text, error)),
+        }
+    })
+}
+
+/// Search a value made by `generate` for which `property` does not hold,
+/// in the cases with the seeds from `seed` on, and shrink it.
+pub fn falsify<T, G, P>(
+    seed: u64,
+    cases: u64,
+    generate: G,
+    property: P,
+) -> Option<Counterexample<T>>
+where
+    G: Fn(&mut Choices) -> T,
+    P: Fn(&T) -> Result<(), String>,
+{
+    (0..cases).find_map(|case| {
+        let seed = seed.wrapping_add(case);
+        let mut choices = Choices::random(seed);
+        let value = generate(&mut choices);
+        property(&value).err().map(|message| {
+            let mut counterexample = Counterexample {
+                seed,
+                value,
+                message,
+                shrinks: 0,
+            };
+            shrink(&generate, &property, choices.made, &mut counterexample);
+            counterexample
+        })
+    })
+}
+
+fn seed() -> u64 {
+    env::var("AOC_CHECK_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(DEFAULT_SEED)
+}
+
+/// Replace the counterexample made from `choices` by simpler ones, until no simpler one is found.
+fn shrink<T, G, P>(generate: &G, property: &P, mut choices: Vec<u64>, best: &mut Counterexample<T>)
+where
+    G: Fn(&mut Choices) -> T,
+    P: Fn(&T) -> Result<(), String>,
+{
+    let attempts = Cell::new(0);
+    // Whether `candidate` fails, in which case it becomes the new counterexample
+    let mut fails = |candidate: Vec<u64>, choices: &mut Vec<u64>| {
+        attempts.set(attempts.get() + 1);
+        let mut replay = Choices::replay(candidate);
+        let value = generate(&mut replay);
+        if !simpler(&replay.made, choices) {
+            return false;
+        }
+        match property(&value) {
+            Ok(()) => false,
+            Err(message) => {
+                *choices = replay.made;
+                best.value = value;
+                best.message = message;
+                best.shrinks += 1;
+                true
+            }
+        }
+    };
+
+    let mut improved = true;
+    while improved && attempts.get() < SHRINK_ATTEMPTS {
+        improved = false;
+
+        // Drop blocks of choices, e.g. an item of a collection with all the choices it is made of
+        for size in [8, 4, 2, 1] {
+            let mut start = 0;
+            while start + size <= choices.len() && attempts.get() < SHRINK_ATTEMPTS {
+                let mut candidate = choices.clone();
+                candidate.drain(start..start + size);
+                if fails(candidate, &mut choices) {
+                    improved = true;
+                } else {
+                    start += 1;
+                }
+            }
+        }
+
+        // Lower each choice as far as possible, assuming values grow with the choices
+        let mut index = 0;
+        while index < choices.len() && attempts.get() < SHRINK_ATTEMPTS {
+            let (mut low, mut high) = (0, choices[index]);
+            while low < high && index < choices.len() && attempts.get() < SHRINK_ATTEMPTS {
+                let middle = low + (high - low) / 2;
+                let mut candidate = choices.clone();
+                candidate[index] = middle;
+                if fails(candidate, &mut choices) {
+                    improved = true;
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            index += 1;
+        }
+    }
+}
+
+/// Fewer choices are simpler, and of as many choices the smaller ones.
+fn simpler(candidate: &[u64], choices: &[u64]) -> bool {
+    (candidate.len(), candidate) < (choices.len(), choices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Part;
+
+    fn numbers(choices: &mut Choices) -> Vec<usize> {
+        choices.vec(0..=20, |choices| choices.below(1000))
+    }
+
+    #[test]
+    fn test_shrink_to_minimal() {
+        let counterexample = falsify(DEFAULT_SEED, CASES, numbers, |numbers| {
+            match numbers.iter().find(|number| **number >= 500) {
+                Some(number) => Err(format!("{} is too large", number)),
+                None => Ok(()),
+            }
+        })
+        .expect("Some number is too large");
+
+        assert_eq!(vec![500], counterexample.value);
+        assert_eq!("500 is too large", counterexample.message);
+    }
+
+    #[test]
+    fn test_shrink_length() {
+        let counterexample = falsify(DEFAULT_SEED, CASES, numbers, |numbers| {
+            if numbers.len() < 5 {
+                Ok(())
+            } else {
+                Err("too long".to_owned())
+            }
+        })
+        .expect("Some list is too long");
+
+        assert_eq!(vec![0; 5], counterexample.value);
+    }
+
+    #[test]
+    fn test_holds() {
+        assert_eq!(
+            None,
+            falsify(DEFAULT_SEED, CASES, numbers, |numbers| {
+                if numbers.len() <= 20 {
+                    Ok(())
+                } else {
+                    Err("too long".to_owned())
+                }
+            })
+        );
+        check(
+            |choices| choices.range(-3..=3),
+            |number| {
+                if number.abs() <= 3 {
+                    Ok(())
+                } else {
+                    Err("out of range".to_owned())
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(|choices| choices.range(i64::MIN..=i64::MAX));
+        round_trip(|choices| choices.string(0..=10, "abc"));
+        round_trip(|choices| *choices.choose(&[Part::One, Part::Two]));
+    }
+
+    #[test]
+    #[should_panic(expected = "'NaN' is parsed as NaN")]
+    fn test_round_trip_fails() {
+        // NaN is parsed again, but is not equal to itself
+        round_trip(|choices| if choices.chance(1, 2) { f64::NAN } else { 1.0 });
+    }
+}
@@ -0,0 +1,325 @@
+//! Command line options understood by every solver.
+
+use crate::input::Source;
+use crate::solution::Part;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Usage of the options common to all solvers
+pub const USAGE: &str =
+    "[--part 1|2] [--format text|json] [--verify] [--bench N] [--PARAMETER VALUE] [--input PATH | PATH | - | --bundled]";
+
+/// Input used when no source is given on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultSource {
+    Stdin,
+    Bundled,
+}
+
+/// How the answers are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// One sentence per answer
+    #[default]
+    Text,
+    /// A single JSON object, including the data supporting the answers
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = UsageError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(UsageError::new(format!(
+                "Format must be text or json, not '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Options selecting the input and the parts to solve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Where to read the input from, `None` if the day has no input
+    pub source: Option<Source>,
+    /// Solve only the given part, both if `None`
+    pub part: Option<Part>,
+    /// How to print the answers
+    pub format: Format,
+    /// Compare the answers to the known correct ones for the bundled input
+    pub verify: bool,
+    /// Run each phase this many times and report their timings, `None` to run them once
+    pub bench: Option<usize>,
+    /// Options for the parameters of the day, by name (including the dashes) and value
+    pub params: Vec<(String, String)>,
+}
+
+impl Options {
+    /// Parse the options from the command line arguments (without program name).
+    ///
+    /// `bundled` is the path of the input shipped with the day, if it has any.
+    pub fn from_args<I, S>(
+        args: I,
+        default: DefaultSource,
+        bundled: Option<&str>,
+    ) -> Result<Options, UsageError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut source = None;
+        let mut part = None;
+        let mut format = Format::default();
+        let mut verify = false;
+        let mut bench = None;
+        let mut params = Vec::new();
+
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            let next_source = match arg.as_str() {
+                "--part" => {
+                    let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                    part = Some(value.parse()?);
+                    continue;
+                }
+                "--format" => {
+                    let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                    format = value.parse()?;
+                    continue;
+                }
+                "--verify" => {
+                    verify = true;
+                    continue;
+                }
+                "--bench" => {
+                    let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                    let runs = value.parse().ok().filter(|runs| *runs > 0).ok_or_else(|| {
+                        UsageError::new(format!("Runs must be a positive number, not '{}'", value))
+                    })?;
+                    bench = Some(runs);
+                    continue;
+                }
+                "--input" => {
+                    let path = args.next().ok_or_else(|| missing_value(&arg))?;
+                    Source::File(PathBuf::from(path))
+                }
+                "--bundled" => match bundled {
+                    Some(path) => Source::Bundled(PathBuf::from(path)),
+                    None => return Err(UsageError::from("There is no bundled input")),
+                },
+                "-" => Source::Stdin,
+                // Whether the day has such a parameter is only known when solving it
+                option if option.starts_with("--") => {
+                    let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                    params.push((option.to_owned(), value));
+                    continue;
+                }
+                path => Source::File(PathBuf::from(path)),
+            };
+            if source.replace(next_source).is_some() {
+                return Err(UsageError::from("Only a single input may be given"));
+            }
+        }
+
+        // The known answers are only valid for the bundled input
+        let default = if verify {
+            DefaultSource::Bundled
+        } else {
+            default
+        };
+        let source = match bundled {
+            Some(path) => Some(source.unwrap_or_else(|| match default {
+                DefaultSource::Stdin => Source::Stdin,
+                DefaultSource::Bundled => Source::Bundled(PathBuf::from(path)),
+            })),
+            None if source.is_some() => {
+                return Err(UsageError::from("This day does not take any input"))
+            }
+            None => None,
+        };
+
+        if verify && !matches!(source, None | Some(Source::Bundled(_))) {
+            return Err(UsageError::from(
+                "Only the answers of the bundled input can be verified",
+            ));
+        }
+        if verify && !params.is_empty() {
+            return Err(UsageError::from(
+                "Only the answers for the default parameters can be verified",
+            ));
+        }
+
+        Ok(Options {
+            source,
+            part,
+            format,
+            verify,
+            bench,
+            params,
+        })
+    }
+}
+
+fn missing_value(option: &str) -> UsageError {
+    UsageError::new(format!("Option '{}' requires a value", option))
+}
+
+/// The command line arguments could not be understood
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError {
+    pub message: String,
+    /// Usage of the command the arguments were given to
+    pub usage: String,
+}
+
+impl UsageError {
+    /// An error in the arguments of a solver.
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        UsageError {
+            message: message.into(),
+            usage: USAGE.to_owned(),
+        }
+    }
+
+    /// The same error, in the arguments of a command with another usage.
+    pub fn with_usage<S: Into<String>>(self, usage: S) -> Self {
+        UsageError {
+            usage: usage.into(),
+            ..self
+        }
+    }
+}
+
+impl From<&str> for UsageError {
+    fn from(message: &str) -> Self {
+        UsageError::new(message)
+    }
+}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (usage: {})", self.message, self.usage)
+    }
+}
+
+impl Error for UsageError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUNDLED: Option<&str> = Some("input/day1.txt");
+
+    fn parse(args: &[&str], default: DefaultSource) -> Result<Options, UsageError> {
+        Options::from_args(args.iter().cloned(), default, BUNDLED)
+    }
+
+    #[test]
+    fn test_default_source() {
+        let stdin = parse(&[], DefaultSource::Stdin).unwrap();
+        let bundled = parse(&[], DefaultSource::Bundled).unwrap();
+
+        assert_eq!(Some(Source::Stdin), stdin.source);
+        assert_eq!(
+            Some(Source::Bundled(PathBuf::from("input/day1.txt"))),
+            bundled.source
+        );
+    }
+
+    #[test]
+    fn test_sources() {
+        let dash = parse(&["-"], DefaultSource::Bundled).unwrap();
+        let path = parse(&["other.txt"], DefaultSource::Stdin).unwrap();
+        let input = parse(&["--input", "other.txt"], DefaultSource::Stdin).unwrap();
+        let bundled = parse(&["--bundled"], DefaultSource::Stdin).unwrap();
+
+        assert_eq!(Some(Source::Stdin), dash.source);
+        assert_eq!(Some(Source::File(PathBuf::from("other.txt"))), path.source);
+        assert_eq!(path, input);
+        assert_eq!(
+            Some(Source::Bundled(PathBuf::from("input/day1.txt"))),
+            bundled.source
+        );
+    }
+
+    #[test]
+    fn test_part() {
+        let options = parse(&["--part", "2", "-"], DefaultSource::Stdin).unwrap();
+
+        assert_eq!(Some(Part::Two), options.part);
+        assert!(parse(&["--part", "3"], DefaultSource::Stdin).is_err());
+        assert!(parse(&["--part"], DefaultSource::Stdin).is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let text = parse(&[], DefaultSource::Stdin).unwrap();
+        let json = parse(&["--format", "json"], DefaultSource::Stdin).unwrap();
+
+        assert_eq!(Format::Text, text.format);
+        assert_eq!(Format::Json, json.format);
+        assert!(parse(&["--format", "yaml"], DefaultSource::Stdin).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let options = parse(&["--verify"], DefaultSource::Stdin).unwrap();
+
+        assert!(options.verify);
+        assert_eq!(
+            Some(Source::Bundled(PathBuf::from("input/day1.txt"))),
+            options.source
+        );
+        assert!(parse(&["--verify", "other.txt"], DefaultSource::Stdin).is_err());
+    }
+
+    #[test]
+    fn test_bench() {
+        let options = parse(&["--bench", "10"], DefaultSource::Stdin).unwrap();
+
+        assert_eq!(Some(10), options.bench);
+        assert_eq!(None, parse(&[], DefaultSource::Stdin).unwrap().bench);
+        assert!(parse(&["--bench", "0"], DefaultSource::Stdin).is_err());
+        assert!(parse(&["--bench", "many"], DefaultSource::Stdin).is_err());
+    }
+
+    #[test]
+    fn test_params() {
+        let options = parse(
+            &["--players", "10", "-", "--size", "7"],
+            DefaultSource::Stdin,
+        )
+        .unwrap();
+
+        assert_eq!(Some(Source::Stdin), options.source);
+        assert_eq!(
+            vec![
+                ("--players".to_owned(), "10".to_owned()),
+                ("--size".to_owned(), "7".to_owned())
+            ],
+            options.params
+        );
+        assert!(parse(&["--verify", "--players", "10"], DefaultSource::Stdin).is_err());
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(parse(&["--verbose"], DefaultSource::Stdin).is_err());
+        assert!(parse(&["a.txt", "b.txt"], DefaultSource::Stdin).is_err());
+    }
+
+    #[test]
+    fn test_no_input() {
+        let options = Options::from_args(Vec::<String>::new(), DefaultSource::Stdin, None);
+        let with_input = Options::from_args(vec!["a.txt"], DefaultSource::Stdin, None);
+
+        assert_eq!(None, options.unwrap().source);
+        assert!(with_input.is_err());
+    }
+}
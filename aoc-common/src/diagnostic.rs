@@ -0,0 +1,241 @@
+//! Errors pointing to the part of the puzzle input which caused them.
+
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+
+/// A piece of the puzzle input, located by its line and column (both 1-based).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    /// Counted in characters, not bytes
+    pub column: usize,
+    pub text: String,
+}
+
+impl Span {
+    /// A span of `text`, starting at `line` and `column`.
+    pub fn new<S: Into<String>>(line: usize, column: usize, text: S) -> Self {
+        Span {
+            line,
+            column,
+            text: text.into(),
+        }
+    }
+
+    /// Locate `part`, which must be a slice of `text`, relative to the start of `text`.
+    ///
+    /// If `part` is not a slice of `text`, the span starts at the beginning of `text`.
+    ///
+    /// ```
+    /// use aoc_common::diagnostic::Span;
+    ///
+    /// let input = "#1 @ 1,3: 4x4\n#2 @ 3,x: 4x4";
+    /// let span = Span::locate(input, &input[21..22]);
+    ///
+    /// assert_eq!(Span::new(2, 8, "x"), span);
+    /// ```
+    pub fn locate(text: &str, part: &str) -> Self {
+        let start = text.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| offset + part.len() <= text.len())
+            .unwrap_or(0);
+
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        Span {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: part.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// An error which may be caused by a specific part of the puzzle input.
+pub trait Diagnostic: Error {
+    /// The offending part of the input, if known.
+    fn span(&self) -> Option<&Span> {
+        None
+    }
+
+    /// The offending part of the input, to move it where the parsed text is in the input.
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        None
+    }
+
+    /// Move the span, which is relative to the parsed text, to the input
+    /// where the parsed text starts on the line with the 0-based `index`.
+    fn on_line(mut self, index: usize) -> Self
+    where
+        Self: Sized,
+    {
+        if let Some(span) = self.span_mut() {
+            span.line += index;
+        }
+        self
+    }
+}
+
+impl Diagnostic for Infallible {}
+
+/// Parse each line of the input, errors point to the line they occurred on.
+pub fn parse_lines<'a, T, E, F>(
+    input: &'a str,
+    mut parse: F,
+) -> impl Iterator<Item = Result<T, E>> + 'a
+where
+    E: Diagnostic,
+    F: FnMut(&'a str) -> Result<T, E> + 'a,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(move |(index, line)| parse(line).map_err(|error| error.on_line(index)))
+}
+
+/// An error displayed with the offending line of the input,
+/// and carets pointing to the offending text.
+///
+/// ```text
+/// line 2, column 8: expected a number, found 'x'
+///   |
+/// 2 | #2 @ 3,x: 4x4
+///   |        ^
+/// ```
+#[derive(Debug)]
+pub struct Annotated<E> {
+    error: E,
+    snippet: Option<String>,
+}
+
+impl<E: Diagnostic> Annotated<E> {
+    /// Annotate the error with the offending line of `input`.
+    pub fn new(error: E, input: &str) -> Self {
+        let snippet = error.span().and_then(|span| {
+            let line = input.lines().nth(span.line.checked_sub(1)?)?;
+            Some(snippet(line, span))
+        });
+        Annotated { error, snippet }
+    }
+
+    /// Annotate the error with the offending `line`, the one its span points to.
+    pub fn with_line(error: E, line: &str) -> Self {
+        let snippet = error.span().map(|span| snippet(line, span));
+        Annotated { error, snippet }
+    }
+
+    /// The error without the offending line, e.g. if the input was not kept.
+    pub fn plain(error: E) -> Self {
+        Annotated {
+            error,
+            snippet: None,
+        }
+    }
+
+    /// The annotated error
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+}
+
+fn snippet(line: &str, span: &Span) -> String {
+    let number = span.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let indent = " ".repeat(span.column.saturating_sub(1));
+    let carets = "^".repeat(span.text.chars().count().max(1));
+
+    format!(
+        "{gutter} |\n{number} | {line}\n{gutter} | {indent}{carets}",
+        gutter = gutter,
+        number = number,
+        line = line,
+        indent = indent,
+        carets = carets
+    )
+}
+
+impl<E: Diagnostic> fmt::Display for Annotated<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(snippet) = &self.snippet {
+            write!(f, "\n{}", snippet)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Diagnostic + 'static> Error for Annotated<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct NotANumber(Span);
+
+    impl fmt::Display for NotANumber {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}: expected a number, found '{}'", self.0, self.0.text)
+        }
+    }
+
+    impl Error for NotANumber {}
+
+    impl Diagnostic for NotANumber {
+        fn span(&self) -> Option<&Span> {
+            Some(&self.0)
+        }
+
+        fn span_mut(&mut self) -> Option<&mut Span> {
+            Some(&mut self.0)
+        }
+    }
+
+    fn parse_number(line: &str) -> Result<u32, NotANumber> {
+        line.trim()
+            .parse()
+            .map_err(|_| NotANumber(Span::locate(line, line.trim())))
+    }
+
+    #[test]
+    fn test_locate() {
+        let text = "ab\ncäd";
+
+        assert_eq!(Span::new(1, 1, "ab"), Span::locate(text, &text[..2]));
+        assert_eq!(Span::new(2, 3, "d"), Span::locate(text, &text[6..]));
+        assert_eq!(Span::new(1, 1, "d"), Span::locate("other", &text[6..]));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let input = "1\n2\n  x3\n4";
+        let numbers: Result<Vec<u32>, _> = parse_lines(input, parse_number).collect();
+
+        assert_eq!(Span::new(3, 3, "x3"), numbers.unwrap_err().0);
+    }
+
+    #[test]
+    fn test_annotated() {
+        let input = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n  eleven";
+        let error = parse_lines(input, parse_number)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+
+        let expected = "line 11, column 3: expected a number, found 'eleven'
+   |
+11 |   eleven
+   |   ^^^^^^";
+        assert_eq!(expected, Annotated::new(error, input).to_string());
+    }
+}
@@ -0,0 +1,112 @@
+//! Generating synthetic puzzle inputs, for stress tests and scaling experiments.
+
+use crate::cli::UsageError;
+use crate::params::{number, positive};
+use crate::random::Rng;
+use crate::solution::Solution;
+
+/// Usage of the options of every generator
+pub const USAGE: &str = "[--seed N] [--size N]";
+
+/// Seed used if none is given on the command line
+pub const DEFAULT_SEED: u64 = 2018;
+
+/// A day whose puzzle input can be generated.
+pub trait Generate: Solution {
+    /// Size of the input generated by default, about that of the bundled input
+    const SIZE: usize;
+
+    /// Generate a valid puzzle input with the given size, the same for the same random numbers.
+    ///
+    /// What the size counts depends on the day, e.g. the number of claims on day 3.
+    /// The size is at least 1.
+    fn generate(rng: &mut Rng, size: usize) -> String;
+}
+
+/// Options of the generators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenerateOptions {
+    pub seed: u64,
+    /// The size of the input, the default of the day if `None`
+    pub size: Option<usize>,
+}
+
+impl GenerateOptions {
+    /// Parse the options from the command line arguments (without program name).
+    pub fn from_args<I, S>(args: I) -> Result<GenerateOptions, UsageError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut options = GenerateOptions {
+            seed: DEFAULT_SEED,
+            size: None,
+        };
+
+        // The errors show the usage of the generators, not that of the solvers
+        let usage = |error: UsageError| error.with_usage(USAGE);
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or_else(|| {
+                usage(UsageError::new(format!(
+                    "Option '{}' requires a value",
+                    arg
+                )))
+            })?;
+            match arg.as_str() {
+                "--seed" => options.seed = number(&arg, &value).map_err(usage)?,
+                "--size" => options.size = Some(positive(&arg, &value).map_err(usage)?),
+                option => {
+                    return Err(usage(UsageError::new(format!(
+                        "Unknown option '{}'",
+                        option
+                    ))))
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Generate the input of the day `G` with the given command line arguments (without program name).
+pub fn generate<G: Generate>(args: &[String]) -> Result<String, UsageError> {
+    let options = GenerateOptions::from_args(args.iter().cloned())?;
+    let mut rng = Rng::new(options.seed);
+    Ok(G::generate(&mut rng, options.size.unwrap_or(G::SIZE)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<GenerateOptions, UsageError> {
+        GenerateOptions::from_args(args.iter().cloned())
+    }
+
+    #[test]
+    fn test_options() {
+        let default = parse(&[]).unwrap();
+        let options = parse(&["--size", "10", "--seed", "3"]).unwrap();
+
+        assert_eq!(DEFAULT_SEED, default.seed);
+        assert_eq!(None, default.size);
+        assert_eq!(3, options.seed);
+        assert_eq!(Some(10), options.size);
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        assert!(parse(&["--size", "0"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--part", "1"]).is_err());
+        assert_eq!(
+            "Option '--size' must be a positive number, not '0' (usage: [--seed N] [--size N])",
+            parse(&["--size", "0"]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unknown option '--part' (usage: [--seed N] [--size N])",
+            parse(&["--part", "1"]).unwrap_err().to_string()
+        );
+    }
+}
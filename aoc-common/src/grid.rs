@@ -0,0 +1,449 @@
+//! A rectangular grid of cells, stored row by row.
+//!
+//! Coordinates are signed, the top-left cell is at the origin of the grid,
+//! which may be anywhere, e.g. at the smallest coordinates of points in the sky.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A coordinate of a cell, x grows to the right and y downwards
+pub type Coordinate = i64;
+
+/// `width` by `height` cells, the top-left one at `origin_x`,`origin_y`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid2D<T> {
+    origin_x: Coordinate,
+    origin_y: Coordinate,
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// A grid with its top-left cell at 0,0, where every cell is `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid2D::with_origin(0, 0, width, height, value)
+    }
+
+    /// A grid with its top-left cell at `origin_x`,`origin_y`, where every cell is `value`.
+    pub fn with_origin(
+        origin_x: Coordinate,
+        origin_y: Coordinate,
+        width: usize,
+        height: usize,
+        value: T,
+    ) -> Self {
+        Grid2D {
+            origin_x,
+            origin_y,
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid2D<T> {
+    /// A grid with its top-left cell at 0,0, where each cell is computed from its coordinates.
+    pub fn from_fn<F>(width: usize, height: usize, mut cell: F) -> Self
+    where
+        F: FnMut(Coordinate, Coordinate) -> T,
+    {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height as Coordinate {
+            for x in 0..width as Coordinate {
+                cells.push(cell(x, y));
+            }
+        }
+        Grid2D {
+            origin_x: 0,
+            origin_y: 0,
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The coordinates of the top-left cell.
+    pub fn origin(&self) -> (Coordinate, Coordinate) {
+        (self.origin_x, self.origin_y)
+    }
+
+    /// Whether the cell at `x`,`y` is part of the grid.
+    pub fn contains(&self, x: Coordinate, y: Coordinate) -> bool {
+        self.index(x, y).is_some()
+    }
+
+    /// Whether the cell at `x`,`y` is part of the grid and on its edge.
+    pub fn on_edge(&self, x: Coordinate, y: Coordinate) -> bool {
+        if !self.contains(x, y) {
+            return false;
+        }
+        let (column, row) = (x - self.origin_x, y - self.origin_y);
+        column == 0
+            || row == 0
+            || column == self.width as Coordinate - 1
+            || row == self.height as Coordinate - 1
+    }
+
+    // The position of the cell in `cells`, `None` if it is outside of the grid
+    fn index(&self, x: Coordinate, y: Coordinate) -> Option<usize> {
+        let column = x.checked_sub(self.origin_x)?;
+        let row = y.checked_sub(self.origin_y)?;
+        if (0..self.width as Coordinate).contains(&column)
+            && (0..self.height as Coordinate).contains(&row)
+        {
+            Some(row as usize * self.width + column as usize)
+        } else {
+            None
+        }
+    }
+
+    // The coordinates of the cell at the position `index` in `cells`
+    fn coordinates(&self, index: usize) -> (Coordinate, Coordinate) {
+        (
+            self.origin_x + (index % self.width) as Coordinate,
+            self.origin_y + (index / self.width) as Coordinate,
+        )
+    }
+
+    /// The cell at `x`,`y`, `None` if it is outside of the grid.
+    pub fn get(&self, x: Coordinate, y: Coordinate) -> Option<&T> {
+        self.index(x, y).map(|index| &self.cells[index])
+    }
+
+    /// The cell at `x`,`y`, `None` if it is outside of the grid.
+    pub fn get_mut(&mut self, x: Coordinate, y: Coordinate) -> Option<&mut T> {
+        match self.index(x, y) {
+            Some(index) => Some(&mut self.cells[index]),
+            None => None,
+        }
+    }
+
+    /// A grid of the same size and origin, where each cell is computed from the one here.
+    pub fn map<U, F>(&self, cell: F) -> Grid2D<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid2D {
+            origin_x: self.origin_x,
+            origin_y: self.origin_y,
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(cell).collect(),
+        }
+    }
+
+    /// All cells row by row, without their coordinates.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// All cells row by row, with their coordinates.
+    pub fn iter(&self) -> impl Iterator<Item = ((Coordinate, Coordinate), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(index, cell)| (self.coordinates(index), cell))
+    }
+
+    /// All cells row by row, with their coordinates.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((Coordinate, Coordinate), &mut T)> {
+        let (origin_x, origin_y, width) = (self.origin_x, self.origin_y, self.width);
+        self.cells.iter_mut().enumerate().map(move |(index, cell)| {
+            let x = origin_x + (index % width) as Coordinate;
+            let y = origin_y + (index / width) as Coordinate;
+            ((x, y), cell)
+        })
+    }
+
+    /// The rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        let width = self.width;
+        (0..self.height).map(move |row| &self.cells[row * width..(row + 1) * width])
+    }
+
+    /// The cells of the row at `y` from left to right, `None` if it is outside of the grid.
+    pub fn row(&self, y: Coordinate) -> Option<&[T]> {
+        let row = y.checked_sub(self.origin_y)?;
+        if (0..self.height as Coordinate).contains(&row) {
+            let start = row as usize * self.width;
+            Some(&self.cells[start..start + self.width])
+        } else {
+            None
+        }
+    }
+
+    /// The cells of the column at `x` from top to bottom, `None` if it is outside of the grid.
+    pub fn column(&self, x: Coordinate) -> Option<impl Iterator<Item = &T>> {
+        let column = x.checked_sub(self.origin_x)?;
+        if (0..self.width as Coordinate).contains(&column) {
+            Some(self.cells.iter().skip(column as usize).step_by(self.width))
+        } else {
+            None
+        }
+    }
+
+    /// The cells left, right, above and below `x`,`y` which are part of the grid.
+    pub fn neighbours(
+        &self,
+        x: Coordinate,
+        y: Coordinate,
+    ) -> impl Iterator<Item = ((Coordinate, Coordinate), &T)> {
+        const OFFSETS: [(Coordinate, Coordinate); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+        self.around(x, y, &OFFSETS)
+    }
+
+    /// The eight cells surrounding `x`,`y`, including the diagonals, which are part of the grid.
+    pub fn all_neighbours(
+        &self,
+        x: Coordinate,
+        y: Coordinate,
+    ) -> impl Iterator<Item = ((Coordinate, Coordinate), &T)> {
+        const OFFSETS: [(Coordinate, Coordinate); 8] = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        self.around(x, y, &OFFSETS)
+    }
+
+    fn around<'a>(
+        &'a self,
+        x: Coordinate,
+        y: Coordinate,
+        offsets: &'static [(Coordinate, Coordinate)],
+    ) -> impl Iterator<Item = ((Coordinate, Coordinate), &'a T)> {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let (x, y) = (x + dx, y + dy);
+            self.get(x, y).map(|cell| ((x, y), cell))
+        })
+    }
+
+    /// A view of `width` by `height` cells, with its top-left cell at `x`,`y`.
+    ///
+    /// `None` unless the view lies fully inside of the grid.
+    pub fn view(
+        &self,
+        x: Coordinate,
+        y: Coordinate,
+        width: usize,
+        height: usize,
+    ) -> Option<View<'_, T>> {
+        let inside = |start: Coordinate, origin: Coordinate, length: usize, size: usize| {
+            let start = start.checked_sub(origin)?;
+            let end = start.checked_add(length as Coordinate)?;
+            Some(start >= 0 && end <= size as Coordinate)
+        };
+        if inside(x, self.origin_x, width, self.width)?
+            && inside(y, self.origin_y, height, self.height)?
+        {
+            Some(View {
+                grid: self,
+                x,
+                y,
+                width,
+                height,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The grid as text, one line per row, with the cells of a row separated by `separator`.
+    pub fn render<F, D>(&self, separator: &str, mut cell: F) -> String
+    where
+        F: FnMut(&T) -> D,
+        D: fmt::Display,
+    {
+        let rows: Vec<String> = self
+            .rows()
+            .map(|row| {
+                let cells: Vec<String> = row.iter().map(|c| cell(c).to_string()).collect();
+                cells.join(separator)
+            })
+            .collect();
+        rows.join("\n")
+    }
+}
+
+/// Panics if the cell is outside of the grid, see `get` to check.
+impl<T> Index<(Coordinate, Coordinate)> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (Coordinate, Coordinate)) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("Cell {},{} is outside of the grid", x, y))
+    }
+}
+
+/// Panics if the cell is outside of the grid, see `get_mut` to check.
+impl<T> IndexMut<(Coordinate, Coordinate)> for Grid2D<T> {
+    fn index_mut(&mut self, (x, y): (Coordinate, Coordinate)) -> &mut T {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("Cell {},{} is outside of the grid", x, y))
+    }
+}
+
+/// A rectangle of cells inside of a grid, which keep their coordinates.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a, T> {
+    grid: &'a Grid2D<T>,
+    x: Coordinate,
+    y: Coordinate,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> View<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The coordinates of the top-left cell, in the grid.
+    pub fn origin(&self) -> (Coordinate, Coordinate) {
+        (self.x, self.y)
+    }
+
+    /// The cell at `x`,`y` of the grid, `None` if it is outside of the view.
+    pub fn get(&self, x: Coordinate, y: Coordinate) -> Option<&'a T> {
+        let column = x - self.x;
+        let row = y - self.y;
+        if (0..self.width as Coordinate).contains(&column)
+            && (0..self.height as Coordinate).contains(&row)
+        {
+            self.grid.get(x, y)
+        } else {
+            None
+        }
+    }
+
+    /// The rows of the view from top to bottom, each from left to right.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let grid = self.grid;
+        let (x, width) = (self.x, self.width);
+        let start = (x - grid.origin_x) as usize;
+        (self.y..self.y + self.height as Coordinate).map(move |y| {
+            let row = grid.row(y).expect("The view is inside of the grid");
+            &row[start..start + width]
+        })
+    }
+
+    /// All cells of the view row by row, with their coordinates in the grid.
+    pub fn iter(&self) -> impl Iterator<Item = ((Coordinate, Coordinate), &'a T)> {
+        let x = self.x;
+        (self.y..)
+            .zip(self.rows())
+            .flat_map(move |(y, row)| (x..).zip(row.iter()).map(move |(x, cell)| ((x, y), cell)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 1 2
+    // 3 4 5
+    fn numbers() -> Grid2D<i64> {
+        Grid2D::from_fn(3, 2, |x, y| 3 * y + x)
+    }
+
+    #[test]
+    fn test_checked_indexing() {
+        let mut grid = numbers();
+        grid[(2, 1)] = 50;
+
+        assert_eq!(Some(&1), grid.get(1, 0));
+        assert_eq!(50, grid[(2, 1)]);
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(None, grid.get(-1, 1));
+        assert_eq!(None, grid.get(0, 2));
+        assert!(!grid.contains(i64::MIN, 0));
+    }
+
+    #[test]
+    fn test_signed_origin() {
+        let mut grid = Grid2D::with_origin(-2, -1, 3, 2, '.');
+        grid[(-2, -1)] = '#';
+        grid[(0, 0)] = '#';
+
+        assert_eq!((-2, -1), grid.origin());
+        assert_eq!(Some(&true), grid.map(|c| *c == '#').get(0, 0));
+        assert_eq!(None, grid.get(1, 0));
+        assert_eq!("# . .\n. . #", grid.render(" ", |c| *c));
+        assert_eq!(
+            vec![(-2, -1), (-1, -1), (0, -1), (-2, 0), (-1, 0), (0, 0)],
+            grid.iter().map(|(xy, _)| xy).collect::<Vec<_>>()
+        );
+        assert!(grid.on_edge(-1, -1));
+        assert!(!grid.on_edge(1, 0));
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = numbers();
+
+        assert_eq!(
+            vec![&[0, 1, 2][..], &[3, 4, 5][..]],
+            grid.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&[3, 4, 5][..]), grid.row(1));
+        assert_eq!(vec![&1, &4], grid.column(1).unwrap().collect::<Vec<_>>());
+        assert!(grid.row(2).is_none());
+        assert!(grid.column(-1).is_none());
+        assert_eq!(3, Grid2D::new(0, 3, 0).rows().count());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = numbers();
+        let values = |cells: Vec<(_, &i64)>| cells.into_iter().map(|(_, v)| *v).collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 3], values(grid.neighbours(0, 0).collect()));
+        assert_eq!(vec![1, 3, 5], values(grid.neighbours(1, 1).collect()));
+        assert_eq!(
+            vec![0, 1, 2, 3, 5],
+            values(grid.all_neighbours(1, 1).collect())
+        );
+        assert_eq!(
+            vec![((2, 0), &2), ((1, 1), &4)],
+            grid.neighbours(2, 1).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_view() {
+        let grid = numbers();
+        let view = grid.view(1, 0, 2, 2).unwrap();
+
+        assert_eq!(
+            vec![&[1, 2][..], &[4, 5][..]],
+            view.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&4), view.get(1, 1));
+        assert_eq!(None, view.get(0, 0));
+        assert_eq!(
+            vec![((1, 0), &1), ((2, 0), &2), ((1, 1), &4), ((2, 1), &5)],
+            view.iter().collect::<Vec<_>>()
+        );
+        assert!(grid.view(2, 0, 2, 1).is_none());
+        assert!(grid.view(-1, 0, 1, 1).is_none());
+    }
+}
@@ -0,0 +1,315 @@
+//! Loading puzzle input from stdin, a file or the input bundled with a day,
+//! either at once or parsing it while it is read.
+
+use crate::diagnostic::{Annotated, Diagnostic, Span};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::{BufRead, BufReader, Read};
+use std::iter;
+use std::path::PathBuf;
+
+/// Where the puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Standard input, e.g. `day07 < input/day7.txt`
+    Stdin,
+    /// A file given as command line argument
+    File(PathBuf),
+    /// The input shipped with the day crate in `dayNN/input/`
+    Bundled(PathBuf),
+}
+
+impl Source {
+    /// Read the whole input from this source.
+    pub fn read(&self) -> Result<String, InputError> {
+        let read = match self {
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map(|_| input)
+            }
+            Source::File(path) | Source::Bundled(path) => fs::read_to_string(path),
+        };
+        read.map_err(|error| InputError {
+            source: self.clone(),
+            error,
+        })
+    }
+}
+
+impl Source {
+    /// Open this source to read it bit by bit.
+    pub fn reader(&self) -> Result<Box<dyn BufRead>, InputError> {
+        match self {
+            Source::Stdin => Ok(Box::new(io::stdin().lock())),
+            Source::File(path) | Source::Bundled(path) => match fs::File::open(path) {
+                Ok(file) => Ok(Box::new(BufReader::new(file))),
+                Err(error) => Err(InputError {
+                    source: self.clone(),
+                    error,
+                }),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "stdin"),
+            Source::File(path) => write!(f, "file '{}'", path.display()),
+            Source::Bundled(path) => write!(f, "bundled input '{}'", path.display()),
+        }
+    }
+}
+
+/// The input source could not be read
+#[derive(Debug)]
+pub struct InputError {
+    source: Source,
+    error: io::Error,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Could not read input from {}: {}",
+            self.source, self.error
+        )
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Parse each line while reading the input, errors point to the line they occurred on.
+///
+/// Only a single line is kept in memory. Reading stops after the input could not be read,
+/// but not after a line could not be parsed.
+pub fn read_lines<R, T, E, F>(
+    mut reader: R,
+    mut parse: F,
+) -> impl Iterator<Item = Result<T, StreamError<E>>>
+where
+    R: BufRead,
+    E: Diagnostic,
+    F: FnMut(&str) -> Result<T, E>,
+{
+    let mut line = String::new();
+    let mut index = 0;
+    let mut failed = false;
+    iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                // Line endings as understood by `str::lines`
+                let text = line.strip_suffix('\n').unwrap_or(&line);
+                let text = text.strip_suffix('\r').unwrap_or(text);
+                let parsed = parse(text).map_err(|error| {
+                    StreamError::Parse(Annotated::with_line(error.on_line(index), text))
+                });
+                index += 1;
+                Some(parsed)
+            }
+            Err(error) => {
+                failed = true;
+                Some(Err(StreamError::Read(error)))
+            }
+        }
+    })
+}
+
+/// Split the input into words separated by whitespace while reading it,
+/// each word located by its span.
+///
+/// Only a single word is kept in memory, however long the lines are.
+pub fn read_words<R: BufRead>(mut reader: R) -> impl Iterator<Item = io::Result<Span>> {
+    let (mut line, mut column) = (1, 1);
+    let mut failed = false;
+    iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        let mut word = Vec::new();
+        let mut start = (line, column);
+        loop {
+            let buffer = match reader.fill_buf() {
+                Ok(buffer) => buffer,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    failed = true;
+                    return Some(Err(error));
+                }
+            };
+            if buffer.is_empty() {
+                break;
+            }
+
+            let mut consumed = 0;
+            let mut complete = false;
+            for &byte in buffer {
+                if byte.is_ascii_whitespace() {
+                    if !word.is_empty() {
+                        complete = true;
+                        break;
+                    }
+                    if byte == b'\n' {
+                        line += 1;
+                        column = 1;
+                    } else {
+                        column += 1;
+                    }
+                } else {
+                    if word.is_empty() {
+                        start = (line, column);
+                    }
+                    word.push(byte);
+                    // Columns count characters, all but the first byte of a character are 0b10xxxxxx
+                    if byte & 0xC0 != 0x80 {
+                        column += 1;
+                    }
+                }
+                consumed += 1;
+            }
+            reader.consume(consumed);
+            if complete {
+                break;
+            }
+        }
+
+        if word.is_empty() {
+            return None;
+        }
+        let word = String::from_utf8(word).map_err(|error| {
+            failed = true;
+            io::Error::new(io::ErrorKind::InvalidData, error)
+        });
+        Some(word.map(|text| Span::new(start.0, start.1, text)))
+    })
+}
+
+/// The input could not be read, or a part of it could not be parsed while reading it
+#[derive(Debug)]
+pub enum StreamError<E> {
+    Read(io::Error),
+    /// Annotated with the offending line, if it is at hand
+    Parse(Annotated<E>),
+}
+
+impl<E: Diagnostic> fmt::Display for StreamError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamError::Read(error) => write!(f, "Could not read input: {}", error),
+            StreamError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<E: Diagnostic + 'static> Error for StreamError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StreamError::Read(error) => Some(error),
+            StreamError::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl<E: Diagnostic> From<E> for StreamError<E> {
+    /// An error found after reading the input, when the offending line is gone
+    fn from(error: E) -> Self {
+        StreamError::Parse(Annotated::plain(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_error_names_source() {
+        let source = Source::File(PathBuf::from("does/not/exist.txt"));
+        let error = source.read().unwrap_err();
+
+        assert!(error.to_string().contains("file 'does/not/exist.txt'"));
+    }
+
+    #[derive(Debug)]
+    struct NotANumber(Span);
+
+    impl fmt::Display for NotANumber {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}: expected a number", self.0)
+        }
+    }
+
+    impl Error for NotANumber {}
+
+    impl Diagnostic for NotANumber {
+        fn span(&self) -> Option<&Span> {
+            Some(&self.0)
+        }
+
+        fn span_mut(&mut self) -> Option<&mut Span> {
+            Some(&mut self.0)
+        }
+    }
+
+    fn parse_number(line: &str) -> Result<u32, NotANumber> {
+        line.trim()
+            .parse()
+            .map_err(|_| NotANumber(Span::locate(line, line.trim())))
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let input = "1\r\n2\n\n  x3\n4";
+        let numbers: Vec<_> = read_lines(input.as_bytes(), parse_number).collect();
+
+        assert_eq!(5, numbers.len());
+        assert_eq!(1, *numbers[0].as_ref().unwrap());
+        assert_eq!(4, *numbers[4].as_ref().unwrap());
+        let expected = "line 4, column 3: expected a number
+  |
+4 |   x3
+  |   ^^";
+        assert_eq!(expected, numbers[3].as_ref().unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_read_lines_stops_on_read_error() {
+        let input = [b'1', b'\n', 0xFF, b'\n', b'2'];
+        let numbers: Vec<_> = read_lines(&input[..], parse_number).collect();
+
+        assert_eq!(2, numbers.len());
+        assert!(matches!(numbers[1], Err(StreamError::Read(_))));
+    }
+
+    #[test]
+    fn test_read_words() {
+        // A tiny buffer splits words and characters
+        let input = "2 3\n  äb 10\n\n 7";
+        let reader = BufReader::with_capacity(2, input.as_bytes());
+        let words: Result<Vec<_>, _> = read_words(reader).collect();
+
+        assert_eq!(
+            vec![
+                Span::new(1, 1, "2"),
+                Span::new(1, 3, "3"),
+                Span::new(2, 3, "äb"),
+                Span::new(2, 6, "10"),
+                Span::new(4, 2, "7"),
+            ],
+            words.unwrap()
+        );
+        assert_eq!(0, read_words(&b" \n "[..]).count());
+    }
+}
@@ -0,0 +1,22 @@
+//! Functionality shared by all solvers of the Advent of Code 2018.
+
+pub mod bench;
+pub mod check;
+pub mod cli;
+pub mod diagnostic;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod params;
+pub mod random;
+pub mod render;
+pub mod run;
+pub mod solution;
+pub mod verify;
+
+pub use crate::diagnostic::{Diagnostic, Span};
+pub use crate::grid::Grid2D;
+pub use crate::json::Json;
+pub use crate::params::Params;
+pub use crate::solution::{Answer, Part, Solution};
@@ -0,0 +1,142 @@
+//! Parameters of a puzzle which may be changed on the command line,
+//! e.g. to solve the puzzle for the numbers given to another user.
+
+use crate::cli::UsageError;
+use std::str::FromStr;
+
+/// The parameters of a day, defaulting to those of the bundled puzzle.
+pub trait Params: Default {
+    /// Usage of the options setting the parameters, empty if there are none
+    const USAGE: &'static str;
+
+    /// Set the parameter of the option `name` (including the dashes) to `value`.
+    ///
+    /// Fails if there is no such option, or the value is invalid.
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError>;
+
+    /// The default parameters, changed by the given options in order.
+    fn from_options(options: &[(String, String)]) -> Result<Self, UsageError> {
+        let mut params = Self::default();
+        for (name, value) in options {
+            params.set(name, value)?;
+        }
+        Ok(params)
+    }
+}
+
+/// No parameters, every option is unknown.
+impl Params for () {
+    const USAGE: &'static str = "";
+
+    fn set(&mut self, name: &str, _value: &str) -> Result<(), UsageError> {
+        Err(unknown_option::<()>(name))
+    }
+}
+
+/// The error for an option not setting any of the parameters `P`.
+pub fn unknown_option<P: Params>(name: &str) -> UsageError {
+    if P::USAGE.is_empty() {
+        UsageError::new(format!("Unknown option '{}'", name))
+    } else {
+        UsageError::new(format!(
+            "Unknown option '{}', the options of this day are {}",
+            name,
+            P::USAGE
+        ))
+    }
+}
+
+/// Parse the `value` of the option `name`, which must be a positive number.
+pub fn positive<T: FromStr + Default + PartialOrd>(
+    name: &str,
+    value: &str,
+) -> Result<T, UsageError> {
+    value
+        .parse()
+        .ok()
+        .filter(|number| *number > T::default())
+        .ok_or_else(|| {
+            UsageError::new(format!(
+                "Option '{}' must be a positive number, not '{}'",
+                name, value
+            ))
+        })
+}
+
+/// Parse the `value` of the option `name`, which must be a number.
+pub fn number<T: FromStr>(name: &str, value: &str) -> Result<T, UsageError> {
+    value.parse().map_err(|_| {
+        UsageError::new(format!(
+            "Option '{}' must be a number, not '{}'",
+            name, value
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Game {
+        players: usize,
+        offset: i32,
+    }
+
+    impl Default for Game {
+        fn default() -> Self {
+            Game {
+                players: 9,
+                offset: 0,
+            }
+        }
+    }
+
+    impl Params for Game {
+        const USAGE: &'static str = "[--players N] [--offset N]";
+
+        fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+            match name {
+                "--players" => self.players = positive(name, value)?,
+                "--offset" => self.offset = number(name, value)?,
+                _ => return Err(unknown_option::<Self>(name)),
+            }
+            Ok(())
+        }
+    }
+
+    fn options(options: &[(&str, &str)]) -> Vec<(String, String)> {
+        options
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_options() {
+        let game = Game::from_options(&options(&[("--players", "3"), ("--offset", "-2")]));
+
+        assert_eq!(
+            Ok(Game {
+                players: 3,
+                offset: -2
+            }),
+            game
+        );
+        assert_eq!(Ok(Game::default()), Game::from_options(&[]));
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        assert!(Game::from_options(&options(&[("--players", "0")])).is_err());
+        assert!(Game::from_options(&options(&[("--offset", "x")])).is_err());
+        assert_eq!(
+            Err(UsageError::new(
+                "Unknown option '--size', the options of this day are [--players N] [--offset N]"
+                    .to_owned()
+            )),
+            Game::from_options(&options(&[("--size", "3")]))
+        );
+        assert!(<()>::from_options(&options(&[("--players", "3")])).is_err());
+    }
+}
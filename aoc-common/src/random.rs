@@ -0,0 +1,118 @@
+//! A small seeded random number generator, to generate puzzle inputs reproducibly.
+
+use std::ops::RangeInclusive;
+
+/// SplitMix64, fast and good enough to generate puzzle inputs, not for cryptography.
+///
+/// The same seed always generates the same numbers, on every platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`.
+    ///
+    /// Panics if `bound` is 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        scale(self.next_u64(), bound)
+    }
+
+    /// A number in the inclusive `range`.
+    ///
+    /// Panics if the range is empty.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        scale_range(self.next_u64(), range)
+    }
+
+    /// True with the probability `numerator / denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    /// A random item of `items`.
+    ///
+    /// Panics if `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// Shuffle `items` in place, every order is equally likely.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// The number in `0..bound` which the random number `raw` stands for, growing with `raw`.
+///
+/// Panics if `bound` is 0.
+pub(crate) fn scale(raw: u64, bound: usize) -> usize {
+    assert!(bound > 0, "Cannot choose a number below 0");
+    // The high bits of the product are close enough to uniform for small bounds
+    ((u128::from(raw) * bound as u128) >> 64) as usize
+}
+
+/// The number in the inclusive `range` which the random number `raw` stands for, growing with `raw`.
+///
+/// Panics if the range is empty.
+pub(crate) fn scale_range(raw: u64, range: RangeInclusive<i64>) -> i64 {
+    let (low, high) = range.into_inner();
+    assert!(low <= high, "Cannot choose a number from an empty range");
+    let span = (high as i128 - low as i128 + 1) as u128;
+    (low as i128 + ((u128::from(raw) * span) >> 64) as i128) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        // The first output of SplitMix64 seeded with 0
+        assert_eq!(0xE220_A839_7B1D_CDAF, numbers(0)[0]);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut rng = Rng::new(2018);
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+            assert!((-5..=5).contains(&rng.range(-5..=5)));
+        }
+        assert_eq!(i64::MIN, rng.range(i64::MIN..=i64::MIN));
+        assert!(!rng.chance(0, 10));
+        assert!(rng.chance(10, 10));
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut items: Vec<_> = (0..20).collect();
+        Rng::new(1).shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+
+        assert_ne!(sorted, items);
+        assert_eq!((0..20).collect::<Vec<_>>(), sorted);
+    }
+}
@@ -0,0 +1,354 @@
+//! Rendering the puzzle of a day as image, for reviews and debugging sessions.
+//!
+//! Images are grids of colors, one per cell of the puzzle, written as binary PPM
+//! or as SVG file, without any dependencies.
+
+use crate::cli::{DefaultSource, Format, Options, UsageError};
+use crate::diagnostic::Annotated;
+use crate::grid::Grid2D;
+use crate::params::Params;
+use crate::solution::Solution;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Usage of the options of every renderer
+pub const USAGE: &str =
+    "--output PATH.ppm|PATH.svg [--scale N] [--PARAMETER VALUE] [--input PATH | PATH | - | --bundled]";
+
+/// A color with 8 bits per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+
+    /// The color `fraction` of the way from this color to `other`, the fraction is clamped to 0..=1.
+    pub fn mix(self, other: Color, fraction: f64) -> Color {
+        let fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        let channel = |from: u8, to: u8| {
+            (f64::from(from) + (f64::from(to) - f64::from(from)) * fraction).round() as u8
+        };
+        Color::rgb(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+        )
+    }
+
+    /// A heat map color from black over red and yellow to white, for `fraction` from 0 to 1.
+    pub fn heat(fraction: f64) -> Color {
+        const STOPS: [Color; 4] = [
+            Color::BLACK,
+            Color::rgb(200, 30, 20),
+            Color::rgb(255, 210, 0),
+            Color::WHITE,
+        ];
+        let position = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64
+        };
+        let stop = (position as usize).min(STOPS.len() - 2);
+        STOPS[stop].mix(STOPS[stop + 1], position - stop as f64)
+    }
+
+    /// A bright color for each index, neighbouring indices are easily told apart.
+    pub fn distinct(index: usize) -> Color {
+        // Successive multiples of the golden ratio spread evenly around the color wheel
+        let hue = (index as f64 * 0.618_033_988_75).fract() * 6.0;
+        let (value, saturation) = (0.95, 0.65);
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as usize {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let channel = |c: f64| ((c + value - chroma) * 255.0).round() as u8;
+        Color::rgb(channel(r), channel(g), channel(b))
+    }
+}
+
+/// `#rrggbb` as used in SVG and HTML
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// The file format of an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary portable pixmap (P6)
+    Ppm,
+    /// Scalable vector graphics, a rectangle per run of equally colored cells in a row
+    Svg,
+}
+
+impl ImageFormat {
+    /// The format given by the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, UsageError> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("ppm") => Ok(ImageFormat::Ppm),
+            Some("svg") => Ok(ImageFormat::Svg),
+            _ => Err(usage_error(format!(
+                "The output '{}' must end in .ppm or .svg",
+                path.display()
+            ))),
+        }
+    }
+
+    /// The image in this format, each cell drawn as a square of `scale` pixels.
+    pub fn encode(self, image: &Grid2D<Color>, scale: usize) -> Vec<u8> {
+        match self {
+            ImageFormat::Ppm => ppm(image, scale),
+            ImageFormat::Svg => svg(image, scale).into_bytes(),
+        }
+    }
+}
+
+/// The image as binary PPM, each cell drawn as a square of `scale` pixels.
+pub fn ppm(image: &Grid2D<Color>, scale: usize) -> Vec<u8> {
+    let header = format!(
+        "P6\n{} {}\n255\n",
+        image.width() * scale,
+        image.height() * scale
+    );
+    let mut bytes = header.into_bytes();
+    for row in image.rows() {
+        let mut line = Vec::with_capacity(row.len() * scale * 3);
+        for color in row {
+            for _ in 0..scale {
+                line.extend_from_slice(&[color.r, color.g, color.b]);
+            }
+        }
+        for _ in 0..scale {
+            bytes.extend_from_slice(&line);
+        }
+    }
+    bytes
+}
+
+/// The image as SVG document, each cell drawn as a square of `scale` pixels.
+pub fn svg(image: &Grid2D<Color>, scale: usize) -> String {
+    let mut svg = format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" "#,
+            r#"viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+            "\n"
+        ),
+        image.width() * scale,
+        image.height() * scale,
+        image.width(),
+        image.height()
+    );
+    for (y, row) in image.rows().enumerate() {
+        let mut x = 0;
+        for run in row.chunk_by(|a, b| a == b) {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"{}\"/>\n",
+                x,
+                y,
+                run.len(),
+                run[0]
+            ));
+            x += run.len();
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// A day whose puzzle can be drawn as image.
+pub trait Render: Solution {
+    /// Draw the parsed input with the given parameters, one color per cell.
+    fn render(input: &Self::Input, params: &Self::Params) -> Result<Grid2D<Color>, Self::Error>;
+}
+
+/// Options of the renderers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// The image file to write, its extension selects the format
+    pub output: PathBuf,
+    pub format: ImageFormat,
+    /// Size of each cell in pixels
+    pub scale: usize,
+    /// The options selecting the input and the parameters of the day
+    pub solve: Vec<String>,
+}
+
+impl RenderOptions {
+    /// Parse the options from the command line arguments (without program name).
+    pub fn from_args<I, S>(args: I) -> Result<RenderOptions, UsageError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut output = None;
+        let mut scale = 1;
+        let mut solve = Vec::new();
+
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--output" | "--scale" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| usage_error(format!("Option '{}' requires a value", arg)))?;
+                    if arg == "--output" {
+                        output = Some(PathBuf::from(value));
+                    } else {
+                        scale = value
+                            .parse()
+                            .ok()
+                            .filter(|scale| *scale > 0)
+                            .ok_or_else(|| {
+                                usage_error(format!(
+                                    "Scale must be a positive number, not '{}'",
+                                    value
+                                ))
+                            })?;
+                    }
+                }
+                _ => solve.push(arg),
+            }
+        }
+
+        let output = output.ok_or_else(|| usage_error("An output file is required"))?;
+        Ok(RenderOptions {
+            format: ImageFormat::from_path(&output)?,
+            output,
+            scale,
+            solve,
+        })
+    }
+}
+
+/// An error in the arguments of a renderer
+fn usage_error<S: Into<String>>(message: S) -> UsageError {
+    UsageError::new(message).with_usage(USAGE)
+}
+
+/// Render the day `R` with the given command line arguments (without program name).
+///
+/// Returns the path of the written image.
+pub fn render<R: Render>(
+    args: &[String],
+    default: DefaultSource,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let options = RenderOptions::from_args(args.iter().cloned())?;
+    let solve = Options::from_args(options.solve.iter().cloned(), default, R::INPUT)
+        .map_err(|error| error.with_usage(USAGE))?;
+    if solve.part.is_some() || solve.verify || solve.bench.is_some() || solve.format != Format::Text
+    {
+        return Err(Box::new(usage_error(
+            "Only the input and the parameters of the day can be given",
+        )));
+    }
+    let params = R::Params::from_options(&solve.params).map_err(|error| error.with_usage(USAGE))?;
+    let parsed = match &solve.source {
+        Some(source) => R::read(source.reader()?, &params)?,
+        None => R::read(io::empty(), &params)?,
+    };
+    let image = R::render(&parsed, &params).map_err(Annotated::plain)?;
+
+    fs::write(
+        &options.output,
+        options.format.encode(&image, options.scale),
+    )
+    .map_err(|e| {
+        format!(
+            "Could not write image '{}': {}",
+            options.output.display(),
+            e
+        )
+    })?;
+    Ok(options.output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // red  red
+    // blue white
+    fn image() -> Grid2D<Color> {
+        let mut image = Grid2D::new(2, 2, Color::rgb(255, 0, 0));
+        image[(0, 1)] = Color::rgb(0, 0, 255);
+        image[(1, 1)] = Color::WHITE;
+        image
+    }
+
+    #[test]
+    fn test_colors() {
+        assert_eq!("#ff8000", Color::rgb(255, 128, 0).to_string());
+        assert_eq!(
+            Color::rgb(128, 128, 128),
+            Color::BLACK.mix(Color::WHITE, 0.5)
+        );
+        assert_eq!(Color::BLACK, Color::heat(-1.0));
+        assert_eq!(Color::WHITE, Color::heat(1.0));
+        assert_ne!(Color::distinct(0), Color::distinct(1));
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend_from_slice(&[255, 0, 0, 255, 0, 0, 0, 0, 255, 255, 255, 255]);
+        assert_eq!(expected, ppm(&image(), 1));
+
+        let scaled = ppm(&image(), 3);
+        assert!(scaled.starts_with(b"P6\n6 6\n255\n"));
+        assert_eq!(b"P6\n6 6\n255\n".len() + 6 * 6 * 3, scaled.len());
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg(&image(), 10);
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20""#)
+        );
+        assert!(svg.contains(r##"<rect x="0" y="0" width="2" height="1" fill="#ff0000"/>"##));
+        assert!(svg.contains(r##"<rect x="1" y="1" width="1" height="1" fill="#ffffff"/>"##));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_options() {
+        let options = RenderOptions::from_args(vec!["--scale", "4", "--output", "a.SVG", "-"]);
+
+        assert_eq!(
+            Ok(RenderOptions {
+                output: PathBuf::from("a.SVG"),
+                format: ImageFormat::Svg,
+                scale: 4,
+                solve: vec!["-".to_owned()],
+            }),
+            options
+        );
+        assert!(RenderOptions::from_args(vec!["-"]).is_err());
+        assert!(RenderOptions::from_args(vec!["--output", "a.png"]).is_err());
+        assert!(RenderOptions::from_args(vec!["--output", "a.ppm", "--scale", "0"]).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Solving a day as configured on the command line.
+
+use crate::bench::{measure, Bench};
+use crate::cli::{DefaultSource, Format, Options};
+use crate::diagnostic::Annotated;
+use crate::json::Json;
+use crate::params::Params;
+use crate::solution::{Answer, Part, Solution};
+use crate::verify::{verify, Answers};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::process;
+use std::time::Duration;
+
+/// The answers of a single day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub day: u8,
+    /// The answer to part 1, `None` if it was not solved
+    pub part1: Option<Solved>,
+    /// The answer to part 2, `None` if it was not solved
+    pub part2: Option<Solved>,
+    /// Time taken to parse the input and solve the parts, the median if benchmarked
+    pub elapsed: Duration,
+    /// Timings of each phase, if benchmarked
+    pub bench: Option<Bench>,
+    /// Whether the answers were verified to be the known correct ones
+    pub verified: bool,
+    /// How the report is displayed
+    pub format: Format,
+}
+
+/// The answer to a single part, as text and for machine-readable output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solved {
+    pub answer: String,
+    /// An object with the answer value, followed by the data supporting it
+    pub json: Json,
+}
+
+impl Solved {
+    /// Record the answer in both forms.
+    pub fn new<A: Answer>(answer: &A) -> Self {
+        let mut entries = vec![("answer", answer.value())];
+        entries.extend(answer.details());
+        Solved {
+            answer: answer.to_string(),
+            json: Json::object(entries),
+        }
+    }
+}
+
+impl Report {
+    /// The report as JSON object, unsolved parts are `null`.
+    pub fn to_json(&self) -> Json {
+        let part = |solved: &Option<Solved>| {
+            solved
+                .as_ref()
+                .map(|solved| solved.json.clone())
+                .unwrap_or(Json::Null)
+        };
+        let mut entries = vec![
+            ("day", Json::from(self.day)),
+            ("part1", part(&self.part1)),
+            ("part2", part(&self.part2)),
+            ("verified", Json::from(self.verified)),
+            ("elapsed_seconds", Json::from(self.elapsed.as_secs_f64())),
+        ];
+        if let Some(bench) = &self.bench {
+            entries.push(("bench", bench.to_json()));
+        }
+        Json::object(entries)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.format == Format::Json {
+            return write!(f, "{}", self.to_json());
+        }
+
+        let answers = [(Part::One, &self.part1), (Part::Two, &self.part2)];
+        let mut first = true;
+        for (part, solved) in answers.iter() {
+            if let Some(solved) = solved {
+                if !first {
+                    writeln!(f)?;
+                }
+                write!(f, "Day {}, part {}: {}", self.day, part, solved.answer)?;
+                first = false;
+            }
+        }
+        if self.verified {
+            write!(f, "\nDay {}: answers verified", self.day)?;
+        }
+        if let Some(bench) = &self.bench {
+            for (phase, timings) in bench.phases() {
+                write!(
+                    f,
+                    "\nDay {}, {} ({} runs): {}",
+                    self.day, phase, bench.runs, timings
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Solve the day `S` with the given command line arguments (without program name).
+pub fn solve<S: Solution>(
+    args: &[String],
+    default: DefaultSource,
+) -> Result<Report, Box<dyn Error>> {
+    let options = Options::from_args(args.iter().cloned(), default, S::INPUT)?;
+    let params = S::Params::from_options(&options.params)?;
+
+    // A benchmark parses the input several times, so it is read into memory first,
+    // otherwise it is parsed while reading it
+    let input = match (&options.source, options.bench) {
+        (Some(source), Some(_)) => source.read()?,
+        _ => String::new(),
+    };
+    let runs = options.bench.unwrap_or(1);
+    let (parsed, parse_time) = match &options.source {
+        Some(source) if options.bench.is_none() => {
+            let mut reader = source.reader()?;
+            measure(1, || S::read(&mut reader, &params))?
+        }
+        _ => measure(runs, || S::read(input.as_bytes(), &params))?,
+    };
+    let annotate = |error| Annotated::new(error, &input);
+    let solve_part = |part| options.part.map(|only| only == part).unwrap_or(true);
+
+    let (part1, part1_time) = if solve_part(Part::One) {
+        let (answer, timings) = measure(runs, || S::part1(&parsed, &params)).map_err(annotate)?;
+        (Some(Solved::new(&answer)), Some(timings))
+    } else {
+        (None, None)
+    };
+    let (part2, part2_time) = if solve_part(Part::Two) {
+        let (answer, timings) = measure(runs, || S::part2(&parsed, &params)).map_err(annotate)?;
+        (Some(Solved::new(&answer)), Some(timings))
+    } else {
+        (None, None)
+    };
+
+    let bench = Bench {
+        runs,
+        parse: parse_time,
+        part1: part1_time,
+        part2: part2_time,
+    };
+    let elapsed = bench
+        .phases()
+        .iter()
+        .map(|(_, timings)| timings.median)
+        .sum();
+
+    if options.verify {
+        let expected = Answers::read(S::ANSWERS)?;
+        let answers = [(Part::One, &part1), (Part::Two, &part2)];
+        let actual = answers.iter().filter_map(|(part, solved)| {
+            solved
+                .as_ref()
+                .map(|solved| (*part, solved.answer.as_str()))
+        });
+        verify(S::DAY, &expected, actual)?;
+    }
+
+    Ok(Report {
+        day: S::DAY,
+        part1,
+        part2,
+        elapsed,
+        bench: options.bench.map(|_| bench),
+        verified: options.verify,
+        format: options.format,
+    })
+}
+
+/// Entry point of the binary of a single day.
+///
+/// Reads the input from stdin, unless another source is given on the command line.
+pub fn main<S: Solution>() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match solve::<S>(&args, DefaultSource::Stdin) {
+        Ok(report) => println!("{}", report),
+        Err(e) => {
+            eprintln!("FATAL ERROR: {}", e);
+            process::exit(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 1;
+        const INPUT: Option<&'static str> = None;
+        const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/missing.toml");
+
+        type Input = Vec<i64>;
+        type Params = ();
+        type Answer1 = i64;
+        type Answer2 = usize;
+        type Error = Infallible;
+
+        fn parse(_input: &str, _params: &()) -> Result<Self::Input, Self::Error> {
+            Ok(vec![1, 2, 3])
+        }
+
+        fn part1(input: &Self::Input, _params: &()) -> Result<Self::Answer1, Self::Error> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(input: &Self::Input, _params: &()) -> Result<Self::Answer2, Self::Error> {
+            Ok(input.len())
+        }
+    }
+
+    #[test]
+    fn test_solve_parts() {
+        let both = solve::<Sum>(&[], DefaultSource::Stdin).unwrap();
+        let second = solve::<Sum>(&["--part".into(), "2".into()], DefaultSource::Stdin).unwrap();
+
+        let answer = |solved: &Option<Solved>| solved.as_ref().map(|solved| solved.answer.clone());
+        assert_eq!(Some("6".to_owned()), answer(&both.part1));
+        assert_eq!(Some("3".to_owned()), answer(&both.part2));
+        assert_eq!(None, second.part1);
+        assert_eq!(Some("3".to_owned()), answer(&second.part2));
+        assert_eq!("Day 1, part 2: 3", second.to_string());
+    }
+
+    #[test]
+    fn test_json() {
+        let args: Vec<String> = vec!["--format".into(), "json".into()];
+        let mut report = solve::<Sum>(&args, DefaultSource::Stdin).unwrap();
+        report.elapsed = Duration::from_millis(1500);
+
+        assert_eq!(
+            r#"{"day":1,"part1":{"answer":6},"part2":{"answer":3},"verified":false,"elapsed_seconds":1.5}"#,
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        let args: Vec<String> = vec!["--part".into(), "1".into(), "--bench".into(), "5".into()];
+        let report = solve::<Sum>(&args, DefaultSource::Stdin).unwrap();
+        let bench = report.bench.as_ref().unwrap();
+
+        assert_eq!(5, bench.runs);
+        assert!(bench.part1.is_some());
+        assert_eq!(None, bench.part2);
+        assert_eq!(2, report.to_string().matches("(5 runs): min").count());
+    }
+}
@@ -0,0 +1,113 @@
+//! The interface every day implements to be solved by the runners.
+
+use crate::cli::UsageError;
+use crate::diagnostic::{Annotated, Diagnostic};
+use crate::input::StreamError;
+use crate::json::Json;
+use crate::params::Params;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// Solver for both parts of a single day.
+///
+/// The input is parsed once, and both parts are solved on the parsed input.
+/// The parameters of the puzzle are given to each step, most days ignore them.
+pub trait Solution {
+    /// Day of the advent calendar
+    const DAY: u8;
+    /// Path of the input bundled with the day, `None` if the puzzle has no input
+    const INPUT: Option<&'static str>;
+    /// Path of the known correct answers for the bundled input, see `verify`
+    const ANSWERS: &'static str;
+
+    /// The parsed puzzle input
+    type Input;
+    /// Parameters of the puzzle which may be changed on the command line, `()` if there are none
+    type Params: Params;
+    /// Answer of part 1, displayed as expected by the puzzle
+    type Answer1: Answer;
+    /// Answer of part 2, displayed as expected by the puzzle
+    type Answer2: Answer;
+    /// Everything that can go wrong parsing the input or solving the puzzle
+    type Error: Diagnostic + 'static;
+
+    /// Parse the raw puzzle input, which is empty if the day has no input.
+    fn parse(input: &str, params: &Self::Params) -> Result<Self::Input, Self::Error>;
+    /// Parse the puzzle input while reading it, errors show the offending line.
+    ///
+    /// By default the whole input is read before parsing it. Days parsing it bit by bit
+    /// override this, to keep little more than the parsed input in memory.
+    fn read<R: BufRead>(
+        mut reader: R,
+        params: &Self::Params,
+    ) -> Result<Self::Input, StreamError<Self::Error>> {
+        let mut input = String::new();
+        reader
+            .read_to_string(&mut input)
+            .map_err(StreamError::Read)?;
+        Self::parse(&input, params)
+            .map_err(|error| StreamError::Parse(Annotated::new(error, &input)))
+    }
+    /// Solve the first part of the puzzle.
+    fn part1(input: &Self::Input, params: &Self::Params) -> Result<Self::Answer1, Self::Error>;
+    /// Solve the second part of the puzzle.
+    fn part2(input: &Self::Input, params: &Self::Params) -> Result<Self::Answer2, Self::Error>;
+}
+
+/// The answer to a part of the puzzle, displayed as expected by the puzzle.
+pub trait Answer: fmt::Display {
+    /// The answer for machine-readable output, a string unless overridden.
+    fn value(&self) -> Json {
+        Json::from(self.to_string())
+    }
+
+    /// Data supporting the answer, e.g. the guard and minute the answer is computed from.
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        Vec::new()
+    }
+}
+
+macro_rules! number_answer {
+    ($($number:ty),*) => {
+        $(
+            impl Answer for $number {
+                fn value(&self) -> Json {
+                    Json::from(*self)
+                }
+            }
+        )*
+    };
+}
+
+number_answer!(i32, i64, i128, u32, u64, usize);
+
+impl Answer for String {}
+
+/// One of the two parts of each puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = UsageError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(UsageError::new(format!("Part must be 1 or 2, not '{}'", s))),
+        }
+    }
+}
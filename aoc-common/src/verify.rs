@@ -0,0 +1,326 @@
+//! The known correct answers of each day, to check that refactoring did not change them.
+//!
+//! The answers are kept next to the bundled input in `dayNN/answers.toml`:
+//!
+//! ```toml
+//! # Answers for input/day4.txt
+//! part1 = 101194
+//! part2 = 102095
+//! ```
+//!
+//! Only integers and strings are supported as values. Multi-line answers are written
+//! as multi-line strings between `"""`, the newline after the opening quotes is ignored.
+
+use crate::diagnostic::Span;
+use crate::solution::Part;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The expected answers of a day, `None` if a part is not known yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    /// Read the answers file at `path`.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Answers, AnswersError> {
+        let path = path.as_ref();
+        let with_path = |error| AnswersError {
+            path: path.to_owned(),
+            error,
+        };
+        let content = fs::read_to_string(path).map_err(|e| with_path(Problem::Read(e)))?;
+        Answers::parse(&content).map_err(|span| with_path(Problem::Parse(span)))
+    }
+
+    /// Parse the content of an answers file, failing with the span which could not be parsed.
+    pub fn parse(content: &str) -> Result<Answers, Span> {
+        let mut answers = Answers::default();
+        let mut rest = content;
+        while let Some(line) = next_line(&mut rest) {
+            let entry = without_comment(line).trim();
+            if entry.is_empty() {
+                continue;
+            }
+
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| Span::locate(content, entry))?;
+            let (key, value) = (key.trim(), value.trim());
+            let answer = match key {
+                "part1" => &mut answers.part1,
+                "part2" => &mut answers.part2,
+                _ => return Err(Span::locate(content, key)),
+            };
+
+            if value.starts_with("\"\"\"") {
+                // The string continues on the following lines, up to the closing quotes
+                let start = value.as_ptr() as usize - content.as_ptr() as usize + 3;
+                let body = &content[start..];
+                let end = body
+                    .find("\"\"\"")
+                    .ok_or_else(|| Span::locate(content, value))?;
+                let text = &body[..end];
+                *answer = Some(text.strip_prefix('\n').unwrap_or(text).to_owned());
+
+                rest = &body[end + 3..];
+                let trailing = next_line(&mut rest).unwrap_or("");
+                if !without_comment(trailing).trim().is_empty() {
+                    return Err(Span::locate(content, trailing.trim()));
+                }
+            } else {
+                *answer = Some(parse_value(content, value)?);
+            }
+        }
+        Ok(answers)
+    }
+
+    /// The expected answer of `part`, if known.
+    pub fn expected(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+// Advance `rest` past the next line, and return that line without its newline
+fn next_line<'a>(rest: &mut &'a str) -> Option<&'a str> {
+    if rest.is_empty() {
+        return None;
+    }
+    let (line, next) = rest.split_once('\n').unwrap_or((rest, ""));
+    *rest = next;
+    Some(line.strip_suffix('\r').unwrap_or(line))
+}
+
+fn without_comment(line: &str) -> &str {
+    // A '#' inside a string is not a comment
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '#' if !quoted => return &line[..i],
+            '"' if !escaped => quoted = !quoted,
+            _ => (),
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    line
+}
+
+// A single line value: an integer or a string
+fn parse_value(content: &str, value: &str) -> Result<String, Span> {
+    let value = value.trim();
+    let invalid = || Span::locate(content, value);
+
+    if let Some(quoted) = value.strip_prefix('"') {
+        let quoted = quoted.strip_suffix('"').ok_or_else(invalid)?;
+        let mut text = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    _ => return Err(invalid()),
+                },
+                '"' => return Err(invalid()),
+                c => text.push(c),
+            }
+        }
+        Ok(text)
+    } else {
+        value
+            .parse::<i128>()
+            .map(|number| number.to_string())
+            .map_err(|_| invalid())
+    }
+}
+
+/// The answers file could not be read
+#[derive(Debug)]
+pub struct AnswersError {
+    path: PathBuf,
+    error: Problem,
+}
+
+#[derive(Debug)]
+enum Problem {
+    Read(io::Error),
+    Parse(Span),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = self.path.display();
+        match &self.error {
+            Problem::Read(e) => write!(f, "Could not read answers from '{}': {}", path, e),
+            Problem::Parse(span) => write!(
+                f,
+                "Could not parse answers in '{}', {}: expected 'part1' or 'part2' with \
+                 an integer or string, found '{}'",
+                path, span, span.text
+            ),
+        }
+    }
+}
+
+impl Error for AnswersError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.error {
+            Problem::Read(e) => Some(e),
+            Problem::Parse(_) => None,
+        }
+    }
+}
+
+/// An answer which differs from the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub part: Part,
+    /// `None` if there is no expected answer for the part
+    pub expected: Option<String>,
+    pub actual: String,
+}
+
+/// Some answers of a day differ from the expected ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    pub day: u8,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Compare the answers of `day` to the expected ones.
+pub fn verify<'a, I>(day: u8, expected: &Answers, actual: I) -> Result<(), VerifyError>
+where
+    I: IntoIterator<Item = (Part, &'a str)>,
+{
+    let mismatches: Vec<_> = actual
+        .into_iter()
+        .filter(|(part, actual)| expected.expected(*part) != Some(*actual))
+        .map(|(part, actual)| Mismatch {
+            part,
+            expected: expected.expected(part).map(str::to_owned),
+            actual: actual.to_owned(),
+        })
+        .collect();
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(VerifyError { day, mismatches })
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {}: answers changed", self.day)?;
+        for mismatch in &self.mismatches {
+            write!(f, "\npart {}:", mismatch.part)?;
+            match &mismatch.expected {
+                Some(expected) => write_diff(f, expected, &mismatch.actual)?,
+                None => {
+                    write!(f, " no expected answer")?;
+                    write_diff(f, "", &mismatch.actual)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Line by line, unchanged lines are indented, removed lines start with '-' and added with '+'
+fn write_diff(f: &mut fmt::Formatter, expected: &str, actual: &str) -> fmt::Result {
+    let mut expected = expected.lines();
+    let mut actual = actual.lines();
+    loop {
+        match (expected.next(), actual.next()) {
+            (None, None) => return Ok(()),
+            (Some(old), Some(new)) if old == new => write!(f, "\n  {}", old)?,
+            (old, new) => {
+                if let Some(old) = old {
+                    write!(f, "\n- {}", old)?;
+                }
+                if let Some(new) = new {
+                    write!(f, "\n+ {}", new)?;
+                }
+            }
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "# Answers for input/day7.txt
+part1 = \"FDSE#GJ\\\"L\" # the step order
+part2 = 1000
+";
+        let answers = Answers::parse(content).unwrap();
+
+        assert_eq!(Some("FDSE#GJ\"L"), answers.expected(Part::One));
+        assert_eq!(Some("1000"), answers.expected(Part::Two));
+    }
+
+    #[test]
+    fn test_parse_multi_line() {
+        let content = "part1 = \"\"\"
+█ •
+• █\"\"\" # message
+part2 = -3";
+        let answers = Answers::parse(content).unwrap();
+
+        assert_eq!(Some("█ •\n• █"), answers.expected(Part::One));
+        assert_eq!(Some("-3"), answers.expected(Part::Two));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Span::new(2, 1, "part3"),
+            Answers::parse("part1 = 1\npart3 = 2").unwrap_err()
+        );
+        assert_eq!(
+            Span::new(1, 9, "12a"),
+            Answers::parse("part1 = 12a").unwrap_err()
+        );
+        assert!(Answers::parse("part1 = \"\"\"\nopen").is_err());
+        assert!(Answers::parse("part1 = \"open").is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let expected = Answers {
+            part1: Some("a\nb\nc".to_owned()),
+            part2: None,
+        };
+
+        assert!(verify(3, &expected, vec![(Part::One, "a\nb\nc")]).is_ok());
+
+        let error = verify(3, &expected, vec![(Part::One, "a\nx\nc"), (Part::Two, "7")]);
+        assert_eq!(
+            "Day 3: answers changed
+part 1:
+  a
+- b
++ x
+  c
+part 2: no expected answer
++ 7",
+            error.unwrap_err().to_string()
+        );
+    }
+}
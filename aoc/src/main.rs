@@ -0,0 +1,322 @@
+use aoc_common::cli::{DefaultSource, Format, Options, UsageError, USAGE};
+use aoc_common::generate::{self, generate};
+use aoc_common::render::{self, render};
+use aoc_common::run::{solve, Report, Solved};
+use aoc_common::Json;
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+
+type Solver = fn(&[String], DefaultSource) -> Result<Report, Box<dyn Error>>;
+type Generator = fn(&[String]) -> Result<String, UsageError>;
+type Renderer = fn(&[String], DefaultSource) -> Result<PathBuf, Box<dyn Error>>;
+
+// Solvers indexed by day - 1
+const DAYS: &[Solver] = &[
+    solve::<day01::Day01>,
+    solve::<day02::Day02>,
+    solve::<day03::Day03>,
+    solve::<day04::Day04>,
+    solve::<day05::Day05>,
+    solve::<day06::Day06>,
+    solve::<day07::Day07>,
+    solve::<day08::Day08>,
+    solve::<day09::Day09>,
+    solve::<day10::Day10>,
+    solve::<day11::Day11>,
+    solve::<day12::Day12>,
+];
+
+// Input generators indexed by day - 1, `None` if the day has no input
+const GENERATORS: &[Option<Generator>] = &[
+    Some(generate::<day01::Day01>),
+    Some(generate::<day02::Day02>),
+    Some(generate::<day03::Day03>),
+    Some(generate::<day04::Day04>),
+    Some(generate::<day05::Day05>),
+    Some(generate::<day06::Day06>),
+    Some(generate::<day07::Day07>),
+    Some(generate::<day08::Day08>),
+    None,
+    Some(generate::<day10::Day10>),
+    None,
+    Some(generate::<day12::Day12>),
+];
+
+// Renderers indexed by day - 1, `None` if the day has nothing to draw
+const RENDERERS: &[Option<Renderer>] = &[
+    None,
+    None,
+    Some(render::<day03::Day03>),
+    None,
+    None,
+    Some(render::<day06::Day06>),
+    None,
+    None,
+    None,
+    Some(render::<day10::Day10>),
+    Some(render::<day11::Day11>),
+    Some(render::<day12::Day12>),
+];
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("FATAL ERROR: {}", e);
+        process::exit(1)
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    match args {
+        [command, day, options @ ..] if command == "run" => {
+            if day == "all" {
+                run_all(options)
+            } else {
+                let report = solver(day)?(options, DefaultSource::Bundled)?;
+                println!("{}", report);
+                Ok(())
+            }
+        }
+        [command, day, options @ ..] if command == "generate" => {
+            print!("{}", generator(day)?(options)?);
+            Ok(())
+        }
+        [command, day, options @ ..] if command == "render" => {
+            let path = renderer(day)?(options, DefaultSource::Bundled)?;
+            println!("Day {}: image written to '{}'", day, path.display());
+            Ok(())
+        }
+        _ => Err(Box::new(
+            UsageError::new("A command is required").with_usage(format!(
+                "aoc run <DAY|all> {} | aoc generate <DAY> {} | aoc render <DAY> {}",
+                USAGE,
+                generate::USAGE,
+                render::USAGE
+            )),
+        )),
+    }
+}
+
+fn solver(day: &str) -> Result<Solver, UsageError> {
+    day.parse::<usize>()
+        .ok()
+        .filter(|day| *day >= 1)
+        .and_then(|day| DAYS.get(day - 1))
+        .cloned()
+        .ok_or_else(|| UsageError::new(format!("There is no solution for day '{}'", day)))
+}
+
+fn generator(day: &str) -> Result<Generator, UsageError> {
+    let index = day.parse::<usize>().ok().filter(|day| *day >= 1);
+    let error = match index.and_then(|day| GENERATORS.get(day - 1)) {
+        Some(Some(generator)) => return Ok(*generator),
+        Some(None) => format!("Day {} has no input to generate", day),
+        None => format!("There is no solution for day '{}'", day),
+    };
+    Err(UsageError::new(error).with_usage(format!("aoc generate <DAY> {}", generate::USAGE)))
+}
+
+fn renderer(day: &str) -> Result<Renderer, UsageError> {
+    let index = day.parse::<usize>().ok().filter(|day| *day >= 1);
+    let error = match index.and_then(|day| RENDERERS.get(day - 1)) {
+        Some(Some(renderer)) => return Ok(*renderer),
+        Some(None) => format!("Day {} has nothing to render", day),
+        None => format!("There is no solution for day '{}'", day),
+    };
+    Err(UsageError::new(error).with_usage(format!("aoc render <DAY> {}", render::USAGE)))
+}
+
+// Solve every day in sequence, and print all answers as summary table or JSON
+fn run_all(options: &[String]) -> Result<(), Box<dyn Error>> {
+    // The options are the same for all days, only the bundled input differs
+    let format =
+        Options::from_args(options.iter().cloned(), DefaultSource::Bundled, Some(""))?.format;
+    let results: Vec<_> = DAYS
+        .iter()
+        .map(|solve| solve(options, DefaultSource::Bundled))
+        .collect();
+    let failed = results.iter().filter(|result| result.is_err()).count();
+
+    match format {
+        Format::Text => print_summary(results),
+        Format::Json => print_json(results),
+    }
+
+    if failed > 0 {
+        Err(From::from(format!(
+            "{} of {} days failed",
+            failed,
+            DAYS.len()
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+// A single JSON array with the report of each day, or its error
+fn print_json(results: Vec<Result<Report, Box<dyn Error>>>) {
+    let reports = (1..)
+        .zip(results)
+        .map(|(day, result)| match result {
+            Ok(report) => report.to_json(),
+            Err(e) => Json::object(vec![
+                ("day", Json::from(day)),
+                ("error", Json::from(e.to_string())),
+            ]),
+        })
+        .collect();
+    println!("{}", Json::Array(reports));
+}
+
+fn print_summary(results: Vec<Result<Report, Box<dyn Error>>>) {
+    let mut rows = vec![[
+        "Day".to_owned(),
+        "Part 1".to_owned(),
+        "Part 2".to_owned(),
+        "Time".to_owned(),
+    ]];
+    // Answers spanning multiple lines would break the table, so they are printed below it
+    let mut long_answers = Vec::new();
+    for (day, result) in (1..).zip(results) {
+        match result {
+            Ok(report) => {
+                let mut cell = |part: usize, solved: &Option<Solved>| match solved {
+                    Some(solved) if solved.answer.contains('\n') => {
+                        let answer = format!("Day {}, part {}: {}", day, part, solved.answer);
+                        long_answers.push(answer);
+                        "(see below)".to_owned()
+                    }
+                    Some(solved) => solved.answer.to_owned(),
+                    None => "-".to_owned(),
+                };
+                let part1 = cell(1, &report.part1);
+                let part2 = cell(2, &report.part2);
+                rows.push([
+                    day.to_string(),
+                    part1,
+                    part2,
+                    format!("{:.1?}", report.elapsed),
+                ]);
+            }
+            Err(e) => {
+                rows.push([
+                    day.to_string(),
+                    format!("ERROR: {}", e),
+                    "-".to_owned(),
+                    "-".to_owned(),
+                ]);
+            }
+        }
+    }
+
+    print_table(&rows);
+    for answer in long_answers {
+        println!("\n{}", answer);
+    }
+}
+
+fn print_table(rows: &[[String; 4]]) {
+    let mut widths = [0; 4];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<_> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = *width))
+            .collect();
+        println!("{}", cells.join(" | ").trim_end());
+        if i == 0 {
+            let separators: Vec<_> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            println!("{}", separators.join("-|-"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solver() {
+        assert!(solver("1").is_ok());
+        assert!(solver("12").is_ok());
+        assert!(solver("0").is_err());
+        assert!(solver("13").is_err());
+        assert!(solver("seven").is_err());
+    }
+
+    #[test]
+    fn test_run_day() {
+        let args: Vec<String> = vec!["--part".into(), "1".into()];
+        let report = solver("9").unwrap()(&args, DefaultSource::Bundled).unwrap();
+
+        assert_eq!(
+            Some("388131"),
+            report.part1.as_ref().map(|p| p.answer.as_str())
+        );
+        assert_eq!(None, report.part2);
+    }
+
+    #[test]
+    fn test_params() {
+        let args: Vec<String> = ["--part", "1", "--players", "10", "--last-marble", "1618"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let report = solver("9").unwrap()(&args, DefaultSource::Bundled).unwrap();
+
+        assert_eq!(
+            Some("8317"),
+            report.part1.as_ref().map(|p| p.answer.as_str())
+        );
+        assert!(
+            solver("9").unwrap()(&["--workers".into(), "2".into()], DefaultSource::Bundled)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let args: Vec<String> = vec!["--size".into(), "5".into(), "--seed".into(), "7".into()];
+        for day in ["1", "2", "3", "4", "5", "6", "7", "8", "10", "12"].iter() {
+            let input = generator(day).unwrap()(&args).unwrap();
+            let path = env::temp_dir().join(format!("aoc-generated-day{}.txt", day));
+            std::fs::write(&path, &input).unwrap();
+
+            let options = vec![path.to_string_lossy().into_owned()];
+            let report = solver(day).unwrap()(&options, DefaultSource::Bundled);
+            assert!(report.is_ok(), "Day {}: {}", day, report.unwrap_err());
+            assert_eq!(input, generator(day).unwrap()(&args).unwrap());
+        }
+        assert!(generator("9").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        for (day, extension) in [("3", "ppm"), ("6", "svg"), ("10", "ppm"), ("12", "svg")].iter() {
+            let path = env::temp_dir().join(format!("aoc-render-day{}.{}", day, extension));
+            let args = vec!["--output".into(), path.to_string_lossy().into_owned()];
+
+            let written = renderer(day).unwrap()(&args, DefaultSource::Bundled).unwrap();
+            assert_eq!(path, written);
+            assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        }
+        assert!(renderer("1").is_err());
+        assert!(renderer("13").is_err());
+    }
+
+    #[test]
+    fn test_verify_day() {
+        let args: Vec<String> = vec!["--part".into(), "1".into(), "--verify".into()];
+        let report = solver("9").unwrap()(&args, DefaultSource::Bundled).unwrap();
+
+        assert!(report.verified);
+    }
+}
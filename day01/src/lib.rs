@@ -0,0 +1,1065 @@
+//! Day 1: Chronal Calibration
+//!
+//! Sums up a list of frequency changes, and finds the first frequency reached twice
+//! when the list is applied over and over again.
+
+extern crate aoc_common;
+
+use aoc_common::cli::UsageError;
+use aoc_common::diagnostic::{parse_lines, Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::input::{read_lines, StreamError};
+use aoc_common::params::{positive, unknown_option};
+use aoc_common::random::Rng;
+use aoc_common::{Answer, Json, Params, Solution};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::iter;
+use std::str::FromStr;
+
+/// Solution of day 1 for the runners
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/input_day1.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Vec<i64>;
+    type Params = Calibration;
+    type Answer1 = Frequency;
+    type Answer2 = FirstRepeats;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        parse_changes(input)
+    }
+
+    fn read<R: BufRead>(
+        reader: R,
+        _params: &Self::Params,
+    ) -> Result<Self::Input, StreamError<Self::Error>> {
+        read_changes(reader).collect()
+    }
+
+    fn part1(
+        changes: &Self::Input,
+        calibration: &Self::Params,
+    ) -> Result<Self::Answer1, Self::Error> {
+        frequency(changes, calibration.width)
+    }
+
+    fn part2(
+        changes: &Self::Input,
+        calibration: &Self::Params,
+    ) -> Result<Self::Answer2, Self::Error> {
+        // The frequencies of the first pass must fit, the later ones are shifted by the drift
+        frequency(changes, calibration.width)?;
+        let repeats: Vec<Repeat> = repeats(changes).take(calibration.repeats).collect();
+        for repeat in &repeats {
+            calibration.width.fit(Some(repeat.frequency))?;
+        }
+        if repeats.is_empty() {
+            return Err(PuzzleError::NoRepeat);
+        }
+        Ok(FirstRepeats(repeats))
+    }
+}
+
+/// How many of the repeated frequencies part 2 lists, 1 in the puzzle,
+/// and the integers the frequencies must fit into, 64 bits by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    pub repeats: usize,
+    pub width: Width,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration {
+            repeats: 1,
+            width: Width::I64,
+        }
+    }
+}
+
+impl Params for Calibration {
+    const USAGE: &'static str = "[--repeats N] [--integer i64|i128]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--repeats" => self.repeats = positive(name, value)?,
+            "--integer" => {
+                self.width = match value {
+                    "i64" => Width::I64,
+                    "i128" => Width::I128,
+                    _ => {
+                        return Err(UsageError::new(format!(
+                            "Option '{}' must be i64 or i128, not '{}'",
+                            name, value
+                        )))
+                    }
+                }
+            }
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+/// A frequency, wide enough to sum up any list of changes without overflowing
+pub type Frequency = i128;
+
+/// The integers the frequencies must fit into, an overflow is an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    I64,
+    I128,
+}
+
+impl Width {
+    /// The frequency, if it was computed without overflow and fits into this width.
+    pub fn fit(self, frequency: Option<Frequency>) -> Result<Frequency, PuzzleError> {
+        match (self, frequency) {
+            (Width::I64, Some(frequency)) if i64::try_from(frequency).is_ok() => Ok(frequency),
+            (Width::I128, Some(frequency)) => Ok(frequency),
+            _ => Err(PuzzleError::Overflow(self)),
+        }
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Width::I64 => write!(f, "i64"),
+            Width::I128 => write!(f, "i128"),
+        }
+    }
+}
+
+/// The first frequencies reached twice, in the order they are reached the second time,
+/// at least one. The answer is the first of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirstRepeats(pub Vec<Repeat>);
+
+/// Just the answer for a single repeat, otherwise a line explaining each repeat
+impl fmt::Display for FirstRepeats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.as_slice() {
+            [repeat] => write!(f, "{}", repeat.frequency),
+            repeats => {
+                for repeat in repeats {
+                    write!(f, "\n{}", repeat)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Answer for FirstRepeats {
+    fn value(&self) -> Json {
+        Json::from(self.0[0].frequency)
+    }
+
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        let mut details = vec![
+            ("first", Json::from(self.0[0].first)),
+            ("again", Json::from(self.0[0].again)),
+        ];
+        if self.0.len() > 1 {
+            details.push(("repeats", Json::from(self.0.clone())));
+        }
+        details
+    }
+}
+
+impl Generate for Day01 {
+    const SIZE: usize = 1000;
+
+    /// `size` frequency changes between -20 and +20.
+    ///
+    /// The resulting frequency is smaller than the number of changes, so two of the
+    /// partial sums are congruent modulo it, and a frequency is reached twice.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const LIMIT: i64 = 20;
+        let mut changes: Vec<i64> = (0..size).map(|_| rng.range(-LIMIT..=LIMIT)).collect();
+
+        let bound = size as i64 - 1;
+        let target = rng.range(-bound..=bound);
+        let mut sum: i64 = changes.iter().sum();
+        while sum != target {
+            let step = (target - sum).signum();
+            let change = &mut changes[rng.below(size)];
+            if (*change + step).abs() <= LIMIT {
+                *change += step;
+                sum += step;
+            }
+        }
+
+        changes
+            .iter()
+            .map(|change| format!("{:+}\n", change))
+            .collect()
+    }
+}
+
+/// Everything that can go wrong on day 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A line is not a frequency change
+    Parse { span: Span },
+    /// No frequency is ever reached twice, however often the changes are repeated
+    NoRepeat,
+    /// A line is not part of a checkpoint of a `FrequencyTracker`
+    Checkpoint { span: Span, expected: &'static str },
+    /// A frequency does not fit into the integers of the given width
+    Overflow(Width),
+    /// A tracker counted more changes than fit into a `usize`
+    ChangesOverflow,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span } => write!(
+                f,
+                "{}: expected a frequency change like '+7', found '{}'",
+                span, span.text
+            ),
+            PuzzleError::NoRepeat => write!(f, "No frequency is ever reached twice"),
+            PuzzleError::Overflow(Width::I64) => write!(
+                f,
+                "A frequency does not fit into an i64, try '--integer i128'"
+            ),
+            PuzzleError::Overflow(width) => {
+                write!(f, "A frequency does not fit into an {}", width)
+            }
+            PuzzleError::Checkpoint { span, expected } => {
+                write!(f, "{}: expected {}, found '{}'", span, expected, span.text)
+            }
+            PuzzleError::ChangesOverflow => write!(f, "There are too many changes to count"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span } | PuzzleError::Checkpoint { span, .. } => Some(span),
+            PuzzleError::NoRepeat | PuzzleError::Overflow(_) | PuzzleError::ChangesOverflow => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span } | PuzzleError::Checkpoint { span, .. } => Some(span),
+            PuzzleError::NoRepeat | PuzzleError::Overflow(_) | PuzzleError::ChangesOverflow => None,
+        }
+    }
+}
+
+/// Parse the signed frequency changes, e.g. `+7` or `-3`, separated by line breaks,
+/// commas or whitespace as in `+1, -2, +3, +1`.
+///
+/// Blank lines and comments from `#` to the end of the line are skipped.
+pub fn parse_changes(input: &str) -> Result<Vec<i64>, PuzzleError> {
+    let mut changes = Vec::new();
+    for line in parse_lines(input, parse_line) {
+        changes.extend(line?);
+    }
+    Ok(changes)
+}
+
+/// The frequency changes, parsed one line at a time while reading them.
+pub fn read_changes<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<i64, StreamError<PuzzleError>>> {
+    read_lines(reader, parse_line).flat_map(|line| {
+        let (changes, error) = match line {
+            Ok(changes) => (changes, None),
+            Err(error) => (Vec::new(), Some(error)),
+        };
+        changes.into_iter().map(Ok).chain(error.map(Err))
+    })
+}
+
+fn parse_line(line: &str) -> Result<Vec<i64>, PuzzleError> {
+    let content = line.split('#').next().unwrap_or_default();
+    content
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|change| !change.is_empty())
+        .map(|change| {
+            change.parse().map_err(|_| PuzzleError::Parse {
+                span: Span::locate(line, change),
+            })
+        })
+        .collect()
+}
+
+/// The resulting frequency after applying all changes, starting from 0.
+///
+/// Fails if any frequency reached on the way does not fit into `width`.
+pub fn frequency(input: &[i64], width: Width) -> Result<Frequency, PuzzleError> {
+    input.iter().try_fold(0, |current: Frequency, change| {
+        width.fit(current.checked_add(Frequency::from(*change)))
+    })
+}
+
+/// The first frequency reached twice, while repeating the list of changes,
+/// `None` if no frequency is ever reached twice.
+pub fn stable_frequency(input: &[i64]) -> Option<Frequency> {
+    repeats(input).next().map(|repeat| repeat.frequency)
+}
+
+/// When a frequency is reached while repeating the list of changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reached {
+    /// The starting frequency, before any change
+    Start,
+    /// After the change `index` of the pass `pass` over the list, both 0-based
+    Change { pass: u64, index: usize },
+}
+
+impl fmt::Display for Reached {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reached::Start => write!(f, "at the start"),
+            Reached::Change { pass, index } => write!(f, "in pass {} by change {}", pass, index),
+        }
+    }
+}
+
+impl From<Reached> for Json {
+    fn from(reached: Reached) -> Self {
+        match reached {
+            Reached::Start => Json::Null,
+            Reached::Change { pass, index } => Json::object(vec![
+                ("pass", Json::from(pass)),
+                ("index", Json::from(index)),
+            ]),
+        }
+    }
+}
+
+/// A frequency reached a second time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    pub frequency: Frequency,
+    /// When the frequency is reached the first time
+    pub first: Reached,
+    /// When the frequency is reached the second time
+    pub again: Reached,
+}
+
+impl fmt::Display for Repeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} reached again {}, first {}",
+            self.frequency, self.again, self.first
+        )
+    }
+}
+
+impl From<Repeat> for Json {
+    fn from(repeat: Repeat) -> Self {
+        Json::object(vec![
+            ("frequency", Json::from(repeat.frequency)),
+            ("first", Json::from(repeat.first)),
+            ("again", Json::from(repeat.again)),
+        ])
+    }
+}
+
+/// The frequencies reached twice while repeating the list of changes,
+/// in the order in which they are reached the second time.
+///
+/// Each pass over the list shifts all frequencies of the pass by the resulting frequency
+/// (the drift), so only frequencies congruent modulo the drift can meet. Sorted by their
+/// residue and value, the frequencies of the first pass take turns: each one is reached
+/// again by the one before it in the same residue class, after as many passes as they are
+/// drifts apart, until the one after it takes over. This finds the repeats, or proves there
+/// are none, in O(n log n) without simulating the passes.
+///
+/// The repeats end early if a pass no longer fits into 64 bits.
+pub fn repeats(input: &[i64]) -> Repeats {
+    // The frequencies of the first pass, before each change is applied, mirrored
+    // for a negative drift so they always grow from pass to pass. They cannot overflow,
+    // as there are less than 2^64 changes of less than 2^63 each.
+    let mut partial: Vec<Frequency> = iter::once(0)
+        .chain(input.iter().scan(0, |current: &mut Frequency, change| {
+            *current += Frequency::from(*change);
+            Some(*current)
+        }))
+        .collect();
+    let drift = partial.pop().expect("The start is always reached");
+    let sign = if drift < 0 { -1 } else { 1 };
+    for current in &mut partial {
+        *current *= sign;
+    }
+    let drift = drift.abs();
+
+    // The steps at which each frequency of the first pass is reached, by value and residue
+    let mut steps: HashMap<Frequency, Vec<u128>> = HashMap::new();
+    for (step, current) in partial.iter().enumerate() {
+        steps.entry(*current).or_default().push(step as u128);
+    }
+    let mut frequencies: Vec<(Frequency, Frequency)> = steps
+        .keys()
+        .map(|current| {
+            let residue = if drift == 0 {
+                0
+            } else {
+                current.rem_euclid(drift)
+            };
+            (residue, *current)
+        })
+        .collect();
+    frequencies.sort_unstable();
+
+    let n = input.len() as u128;
+    let mut pending = BinaryHeap::new();
+    for (position, (residue, current)) in frequencies.iter().enumerate() {
+        let reached = &steps[current];
+        let below = position
+            .checked_sub(1)
+            .map(|below| frequencies[below])
+            .filter(|(other, _)| other == residue);
+        let above = frequencies
+            .get(position + 1)
+            .filter(|(other, _)| other == residue);
+
+        let again = if drift == 0 {
+            // Every pass repeats the first one, which ends where it started
+            reached.get(1).cloned().unwrap_or(n + reached[0])
+        } else if let Some(again) = reached.get(1) {
+            *again
+        } else if let Some((_, lower)) = below {
+            let passes = ((current - lower) / drift) as u128;
+            match passes
+                .checked_mul(n)
+                .and_then(|step| step.checked_add(steps[&lower][0]))
+            {
+                Some(again) => again,
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+        let left = match above {
+            _ if drift == 0 => Some(1),
+            Some((_, higher)) => Some(((higher - current) / drift) as u128),
+            None => None,
+        };
+        pending.push(Reverse(Pending {
+            again,
+            first: reached[0],
+            frequency: *current,
+            left,
+        }));
+    }
+
+    Repeats {
+        changes: n,
+        sign,
+        drift,
+        pending,
+    }
+}
+
+/// The frequencies reached twice, see `repeats`.
+#[derive(Debug, Clone)]
+pub struct Repeats {
+    changes: u128,
+    sign: Frequency,
+    drift: Frequency,
+    pending: BinaryHeap<Reverse<Pending>>,
+}
+
+/// The frequencies reached twice by the same pair of frequencies of the first pass,
+/// one drift apart, starting with the one reached the second time at step `again`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Pending {
+    again: u128,
+    first: u128,
+    /// Mirrored for a negative drift
+    frequency: Frequency,
+    /// How many frequencies are left, `None` if infinitely many
+    left: Option<u128>,
+}
+
+impl Repeats {
+    // Steps count the changes applied so far
+    fn reached(&self, step: u128) -> Option<Reached> {
+        match step.checked_sub(1) {
+            None => Some(Reached::Start),
+            Some(change) => Some(Reached::Change {
+                pass: u64::try_from(change / self.changes).ok()?,
+                index: (change % self.changes) as usize,
+            }),
+        }
+    }
+}
+
+impl Iterator for Repeats {
+    type Item = Repeat;
+
+    fn next(&mut self) -> Option<Repeat> {
+        let Reverse(next) = self.pending.pop()?;
+        let repeat = Repeat {
+            frequency: next.frequency * self.sign,
+            first: self.reached(next.first)?,
+            again: self.reached(next.again)?,
+        };
+
+        let left = next.left.map(|left| left - 1);
+        let again = next.again.checked_add(self.changes);
+        let frequency = next.frequency.checked_add(self.drift);
+        if let (true, Some(again), Some(frequency)) = (left != Some(0), again, frequency) {
+            self.pending.push(Reverse(Pending {
+                again,
+                first: next.first + self.changes,
+                frequency,
+                left,
+            }));
+        }
+        Some(repeat)
+    }
+}
+
+/// Tracks the frequency while the changes arrive one at a time, e.g. from a live stream,
+/// and reports the first repeated frequency as soon as it is reached.
+///
+/// A stream is a single pass, so repeats are reached in pass 0, by the change with the
+/// index of all changes applied so far. Once the first repeat is found, the frequencies
+/// reached before are forgotten.
+///
+/// The tracker is displayed as checkpoint, from which it continues when parsed again:
+///
+/// ```text
+/// frequency 3 after 4 changes
+/// 0 after 0
+/// 1 after 1
+/// -1 after 2
+/// 2 after 3
+/// 3 after 4
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrequencyTracker {
+    current: Frequency,
+    changes: usize,
+    /// The number of changes after which each frequency was first reached, until a repeat
+    seen: HashMap<Frequency, usize>,
+    repeat: Option<Repeat>,
+}
+
+impl Default for FrequencyTracker {
+    fn default() -> Self {
+        FrequencyTracker::new()
+    }
+}
+
+impl FrequencyTracker {
+    /// A tracker at the starting frequency 0.
+    pub fn new() -> Self {
+        FrequencyTracker {
+            current: 0,
+            changes: 0,
+            seen: iter::once((0, 0)).collect(),
+            repeat: None,
+        }
+    }
+
+    /// Apply the next change, returns the first repeat if it is reached by this change.
+    ///
+    /// Fails without applying the change if the frequency or the number of changes
+    /// overflows, which a tracker restored from a checkpoint may be close to.
+    pub fn apply(&mut self, change: i64) -> Result<Option<Repeat>, PuzzleError> {
+        let current = self
+            .current
+            .checked_add(Frequency::from(change))
+            .ok_or(PuzzleError::Overflow(Width::I128))?;
+        let changes = self
+            .changes
+            .checked_add(1)
+            .ok_or(PuzzleError::ChangesOverflow)?;
+        self.current = current;
+        self.changes = changes;
+        if self.repeat.is_some() {
+            return Ok(None);
+        }
+
+        match self.seen.insert(self.current, self.changes) {
+            None => Ok(None),
+            Some(first) => {
+                let repeat = Repeat {
+                    frequency: self.current,
+                    first: reached(first),
+                    again: reached(self.changes),
+                };
+                self.repeat = Some(repeat);
+                self.seen = HashMap::new();
+                Ok(Some(repeat))
+            }
+        }
+    }
+
+    /// The frequency after all changes so far
+    pub fn current(&self) -> Frequency {
+        self.current
+    }
+
+    /// Number of changes applied so far
+    pub fn changes(&self) -> usize {
+        self.changes
+    }
+
+    /// The first frequency reached twice, if already reached.
+    pub fn first_repeat(&self) -> Option<Repeat> {
+        self.repeat
+    }
+}
+
+// When the frequency is reached after `changes` changes of the single pass of a stream
+fn reached(changes: usize) -> Reached {
+    match changes.checked_sub(1) {
+        None => Reached::Start,
+        Some(index) => Reached::Change { pass: 0, index },
+    }
+}
+
+// The number of changes after which the frequency is reached
+fn changes(reached: Reached) -> usize {
+    match reached {
+        Reached::Start => 0,
+        Reached::Change { index, .. } => index + 1,
+    }
+}
+
+/// The checkpoint of the tracker, see `FrequencyTracker`
+impl fmt::Display for FrequencyTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "frequency {} after {} changes",
+            self.current, self.changes
+        )?;
+        if let Some(repeat) = &self.repeat {
+            write!(
+                f,
+                "\nrepeat {} after {} and {}",
+                repeat.frequency,
+                changes(repeat.first),
+                changes(repeat.again)
+            )?;
+        }
+        let mut seen: Vec<_> = self.seen.iter().collect();
+        seen.sort_unstable_by_key(|(_, changes)| **changes);
+        for (frequency, changes) in seen {
+            write!(f, "\n{} after {}", frequency, changes)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FrequencyTracker {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let mut tracker = FrequencyTracker {
+            current: 0,
+            changes: 0,
+            seen: HashMap::new(),
+            repeat: None,
+        };
+        for (index, line) in s.lines().enumerate() {
+            let error = |text: &str, expected| {
+                PuzzleError::Checkpoint {
+                    span: Span::locate(line, text),
+                    expected,
+                }
+                .on_line(index)
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let number = |word: &str| word.parse().map_err(|_| error(word, "a number"));
+            let count = |word: &str| word.parse().map_err(|_| error(word, "a number of changes"));
+
+            match (index, words.as_slice()) {
+                (0, ["frequency", current, "after", changes, "changes"]) => {
+                    tracker.current = number(current)?;
+                    tracker.changes = count(changes)?;
+                }
+                (0, _) => return Err(error(line, "'frequency F after N changes'")),
+                (1, ["repeat", frequency, "after", first, "and", again]) => {
+                    tracker.repeat = Some(Repeat {
+                        frequency: number(frequency)?,
+                        first: reached(count(first)?),
+                        again: reached(count(again)?),
+                    });
+                }
+                (_, [frequency, "after", changes]) if tracker.repeat.is_none() => {
+                    tracker.seen.insert(number(frequency)?, count(changes)?);
+                }
+                _ => {
+                    return Err(error(
+                        line,
+                        "'F after N', a frequency reached after N changes",
+                    ))
+                }
+            }
+        }
+        if tracker.repeat.is_none() && !tracker.seen.contains_key(&tracker.current) {
+            return Err(PuzzleError::Checkpoint {
+                span: Span::locate(s, s.lines().next().unwrap_or(s)),
+                expected: "the current frequency among those reached",
+            });
+        }
+        Ok(tracker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_pos() {
+        let input = vec![1, 1, 1];
+        let result = frequency(&input, Width::I64);
+        assert_eq!(result, Ok(3))
+    }
+
+    #[test]
+    fn test_sum_0() {
+        let input = vec![1, 1, -2];
+        let result = frequency(&input, Width::I64);
+        assert_eq!(result, Ok(0))
+    }
+
+    #[test]
+    fn test_linear_neg() {
+        let input = vec![-1, -2, -3];
+        let result = frequency(&input, Width::I64);
+        assert_eq!(result, Ok(-6))
+    }
+
+    #[test]
+    fn test_parse_error_line() {
+        let error = parse_changes("+1\n-2\n+x\n").unwrap_err();
+
+        assert_eq!(
+            PuzzleError::Parse {
+                span: Span::new(3, 1, "+x")
+            },
+            error
+        );
+    }
+
+    #[test]
+    fn test_parse_lists() {
+        let input = "# the examples of the puzzle\n+1, -2, +3, +1\n\n+3 +3\t+4 # another one\n";
+
+        assert_eq!(Ok(vec![1, -2, 3, 1, 3, 3, 4]), parse_changes(input));
+        assert_eq!(
+            Err(PuzzleError::Parse {
+                span: Span::new(2, 5, "x2")
+            }),
+            parse_changes("+1\n+1, x2")
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Only the frequency after the second change overflows
+        let changes = vec![i64::MAX, 1, -i64::MAX];
+
+        assert_eq!(
+            Err(PuzzleError::Overflow(Width::I64)),
+            frequency(&changes, Width::I64)
+        );
+        assert_eq!(Ok(1), frequency(&changes, Width::I128));
+
+        let wide = Calibration::from_options(&[("--integer".to_owned(), "i128".to_owned())]);
+        assert_eq!(
+            Some(Frequency::from(i64::MAX) + 1),
+            Day01::part2(&changes, &wide.unwrap())
+                .ok()
+                .map(|answer| answer.0[0].frequency)
+        );
+        assert_eq!(
+            Err(PuzzleError::Overflow(Width::I64)),
+            Day01::part2(&changes, &Calibration::default())
+        );
+    }
+
+    #[test]
+    fn test_read_changes() {
+        let changes: Vec<_> = read_changes("+1\n-2\n+x\n+3".as_bytes()).collect();
+
+        assert_eq!(4, changes.len());
+        let listed: Result<Vec<_>, _> = read_changes("+1, -2\n# done\n+3".as_bytes()).collect();
+        assert_eq!(vec![1, -2, 3], listed.unwrap());
+        assert_eq!(-2, *changes[1].as_ref().unwrap());
+        assert_eq!(
+            "line 3, column 1: expected a frequency change like '+7', found '+x'\n  |\n3 | +x\n  | ^^",
+            changes[2].as_ref().unwrap_err().to_string()
+        );
+    }
+
+    // part two
+    #[test]
+    fn test_twice_0() {
+        let input = vec![1, -1];
+        let result = stable_frequency(&input);
+        assert_eq!(result, Some(0))
+    }
+    #[test]
+    fn test_twice_10() {
+        let input = vec![3, 3, 4, -2, -4];
+        let result = stable_frequency(&input);
+        assert_eq!(result, Some(10))
+    }
+    #[test]
+    fn test_twice_5() {
+        let input = vec![-6, 3, 8, 5, -6];
+        let result = stable_frequency(&input);
+        assert_eq!(result, Some(5))
+    }
+    #[test]
+    fn test_twice_14() {
+        let input = vec![7, 7, -2, -7, -4];
+        let result = stable_frequency(&input);
+        assert_eq!(result, Some(14))
+    }
+
+    #[test]
+    fn test_never_twice() {
+        assert_eq!(None, stable_frequency(&[]));
+        assert_eq!(None, stable_frequency(&[1]));
+        assert_eq!(None, stable_frequency(&[5, 1]));
+        assert_eq!(None, stable_frequency(&[-3, -4]));
+        assert_eq!(
+            Err(PuzzleError::NoRepeat),
+            Day01::part2(&vec![5, 1], &Calibration::default())
+        );
+    }
+
+    #[test]
+    fn test_twice_after_many_passes() {
+        // 0 is reached again by 1 + 1 + ... after 10001 passes
+        assert_eq!(Some(10_001), stable_frequency(&[10_001, -10_000]));
+        assert_eq!(Some(-10_001), stable_frequency(&[-10_001, 10_000]));
+    }
+
+    /// The first `count` frequencies reached twice within `steps` changes
+    fn simulate(input: &[i64], steps: usize, count: usize) -> Vec<Repeat> {
+        let mut first = HashMap::new();
+        let mut repeats = Vec::new();
+        let mut current: Frequency = 0;
+        first.insert(current, Some(Reached::Start));
+        for (step, change) in input.iter().cycle().take(steps).enumerate() {
+            current += Frequency::from(*change);
+            let reached = Reached::Change {
+                pass: (step / input.len()) as u64,
+                index: step % input.len(),
+            };
+            match first.get_mut(&current) {
+                None => {
+                    first.insert(current, Some(reached));
+                }
+                // Only the second time counts as repeat
+                Some(earlier) => {
+                    if let Some(earlier) = earlier.take() {
+                        repeats.push(Repeat {
+                            frequency: current,
+                            first: earlier,
+                            again: reached,
+                        });
+                    }
+                }
+            }
+            if repeats.len() == count {
+                break;
+            }
+        }
+        repeats
+    }
+
+    #[test]
+    fn test_repeats_as_simulated() {
+        aoc_common::check::check(
+            |choices| choices.vec(0..=8, |choices| choices.range(-10..=10)),
+            |input| {
+                // The partial sums are at most 160 apart, so the first repeat happens within
+                // 161 passes of up to 8 changes, and each further one within another pass
+                let expected = simulate(input, 170 * 8, 5);
+                match repeats(input).take(5).collect::<Vec<_>>() {
+                    ref found if *found == expected => Ok(()),
+                    found => Err(format!("found {:?}, simulated {:?}", found, expected)),
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_repeat_positions() {
+        let repeat = repeats(&[3, 3, 4, -2, -4]).next();
+
+        assert_eq!(
+            Some(Repeat {
+                frequency: 10,
+                first: Reached::Change { pass: 0, index: 2 },
+                again: Reached::Change { pass: 1, index: 1 },
+            }),
+            repeat
+        );
+        assert_eq!(
+            Some(Reached::Start),
+            repeats(&[1, -1]).next().map(|repeat| repeat.first)
+        );
+    }
+
+    #[test]
+    fn test_list_repeats() {
+        let calibration = Calibration {
+            repeats: 3,
+            ..Calibration::default()
+        };
+        let answer = Day01::part2(&vec![1, -2], &calibration).unwrap();
+
+        assert_eq!(
+            "\n0 reached again in pass 1 by change 0, first at the start\n\
+             -1 reached again in pass 2 by change 0, first in pass 0 by change 1\n\
+             -2 reached again in pass 3 by change 0, first in pass 1 by change 1",
+            answer.to_string()
+        );
+        assert_eq!(Json::from(0), answer.value());
+        assert_eq!("repeats", answer.details()[2].0);
+    }
+
+    #[test]
+    fn test_tracker() {
+        let mut tracker = FrequencyTracker::new();
+
+        let reported: Vec<_> = [3, 3, 4, -2, -4, 3, 3, 4]
+            .iter()
+            .map(|change| tracker.apply(*change).unwrap())
+            .collect();
+
+        let repeat = Repeat {
+            frequency: 10,
+            first: Reached::Change { pass: 0, index: 2 },
+            again: Reached::Change { pass: 0, index: 6 },
+        };
+        assert_eq!(
+            vec![None, None, None, None, None, None, Some(repeat), None],
+            reported
+        );
+        assert_eq!(Some(repeat), tracker.first_repeat());
+        assert_eq!(14, tracker.current());
+        assert_eq!(8, tracker.changes());
+    }
+
+    #[test]
+    fn test_tracker_as_repeats() {
+        aoc_common::check::check(
+            |choices| choices.vec(1..=8, |choices| choices.range(-10..=10)),
+            |input| {
+                let mut tracker = FrequencyTracker::new();
+                let reported = input
+                    .iter()
+                    .cycle()
+                    .take(170 * 8)
+                    .filter_map(|change| tracker.apply(*change).unwrap())
+                    .next();
+                let single_pass = |reached| match reached {
+                    Reached::Change { pass, index } => Reached::Change {
+                        pass: 0,
+                        index: pass as usize * input.len() + index,
+                    },
+                    Reached::Start => Reached::Start,
+                };
+                let expected = repeats(input).next().map(|repeat| Repeat {
+                    first: single_pass(repeat.first),
+                    again: single_pass(repeat.again),
+                    ..repeat
+                });
+                if reported == expected {
+                    Ok(())
+                } else {
+                    Err(format!("reported {:?}, expected {:?}", reported, expected))
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut tracker = FrequencyTracker::new();
+        for change in &[1, -2, 3, 1] {
+            tracker.apply(*change).unwrap();
+        }
+        let checkpoint = tracker.to_string();
+
+        assert_eq!(
+            "frequency 3 after 4 changes\n0 after 0\n1 after 1\n-1 after 2\n2 after 3\n3 after 4",
+            checkpoint
+        );
+        let mut restored: FrequencyTracker = checkpoint.parse().unwrap();
+        assert_eq!(tracker, restored);
+        assert!(restored.apply(-2).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        aoc_common::check::round_trip(|choices| {
+            let mut tracker = FrequencyTracker::new();
+            for change in choices.vec(0..=20, |choices| choices.range(-5..=5)) {
+                tracker.apply(change).unwrap();
+            }
+            tracker
+        });
+    }
+
+    #[test]
+    fn test_checkpoint_near_limits() {
+        let checkpoint = format!(
+            "frequency {0} after 5 changes\n{0} after 5",
+            Frequency::MAX - 1
+        );
+        let mut tracker: FrequencyTracker = checkpoint.parse().unwrap();
+
+        assert_eq!(Ok(None), tracker.apply(1));
+        assert_eq!(Err(PuzzleError::Overflow(Width::I128)), tracker.apply(1));
+        assert_eq!(Frequency::MAX, tracker.current());
+        assert_eq!(6, tracker.changes());
+
+        let checkpoint = format!("frequency 0 after {} changes\n0 after 0", usize::MAX);
+        let mut tracker: FrequencyTracker = checkpoint.parse().unwrap();
+        assert_eq!(Err(PuzzleError::ChangesOverflow), tracker.apply(1));
+        assert_eq!(0, tracker.current());
+    }
+
+    #[test]
+    fn test_checkpoint_error() {
+        let error = "frequency 3 after 2 changes\n0 after 0\n3 after x"
+            .parse::<FrequencyTracker>()
+            .unwrap_err();
+
+        assert_eq!(
+            PuzzleError::Checkpoint {
+                span: Span::new(3, 9, "x"),
+                expected: "a number of changes",
+            },
+            error
+        );
+    }
+}
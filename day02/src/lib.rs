@@ -0,0 +1,1312 @@
+//! Day 2: Inventory Management System
+//!
+//! Computes a checksum over a list of box IDs and finds the two box IDs
+//! which differ in a single letter.
+
+extern crate aoc_common;
+extern crate unicode_normalization;
+extern crate unicode_segmentation;
+
+use aoc_common::cli::UsageError;
+use aoc_common::generate::Generate;
+use aoc_common::params::{positive, unknown_option};
+use aoc_common::random::Rng;
+use aoc_common::{Answer, Diagnostic, Json, Params, Solution};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Solution of day 2 for the runners
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day2.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Vec<String>;
+    type Params = Scanner;
+    type Answer1 = Checksum;
+    type Answer2 = ClosePairs;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        Ok(input.lines().map(str::to_owned).collect())
+    }
+
+    fn part1(ids: &Self::Input, scanner: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        let checksum = checksum(ids, scanner);
+        checksum.value().ok_or(PuzzleError::ChecksumOverflow)?;
+        Ok(checksum)
+    }
+
+    fn part2(ids: &Self::Input, scanner: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let pairs = close_pairs_within(ids, scanner);
+        if pairs.is_empty() {
+            return Err(PuzzleError::NoCommonIds);
+        }
+        let clusters = match scanner.clusters {
+            Some(min_size) => clusters(ids, scanner)
+                .into_iter()
+                .filter(|cluster| cluster.size() >= min_size)
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(ClosePairs {
+            pairs,
+            anomalies: anomalies(ids, scanner.units),
+            clusters,
+        })
+    }
+}
+
+/// How the box IDs are scanned: the IDs are split into `units`, the letters. The checksum
+/// is the product of the number of IDs containing some letter exactly as often as each of
+/// the `multiplicities`, 2 and 3 in the puzzle. Letters are counted case sensitive,
+/// and all letters are counted, unless an alphabet is given.
+///
+/// Two IDs are close if they differ, but at most `distance` apart by the `metric`,
+/// in the puzzle a single substitution. The clusters of close IDs with at least
+/// `clusters` members are reported as well, if given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scanner {
+    pub units: Units,
+    pub multiplicities: BTreeSet<usize>,
+    pub ignore_case: bool,
+    /// The letters counted, split into units like the IDs, all if `None`
+    pub alphabet: Option<String>,
+    pub metric: Metric,
+    pub distance: usize,
+    pub clusters: Option<usize>,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Scanner {
+            units: Units::Chars,
+            multiplicities: [2, 3].iter().cloned().collect(),
+            ignore_case: false,
+            alphabet: None,
+            metric: Metric::Hamming,
+            distance: 1,
+            clusters: None,
+        }
+    }
+}
+
+impl Params for Scanner {
+    const USAGE: &'static str = "[--units chars|graphemes] [--multiplicities N,N,...] \
+         [--case sensitive|insensitive] [--alphabet LETTERS] [--metric hamming|levenshtein] \
+         [--distance N] [--clusters MIN_SIZE]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--units" => {
+                self.units = match value {
+                    "chars" => Units::Chars,
+                    "graphemes" => Units::Graphemes,
+                    _ => {
+                        return Err(UsageError::new(format!(
+                            "Option '{}' must be chars or graphemes, not '{}'",
+                            name, value
+                        )))
+                    }
+                }
+            }
+            "--multiplicities" => {
+                self.multiplicities = value
+                    .split(',')
+                    .map(|multiplicity| positive(name, multiplicity.trim()))
+                    .collect::<Result<_, _>>()?
+            }
+            "--case" => {
+                self.ignore_case = match value {
+                    "sensitive" => false,
+                    "insensitive" => true,
+                    _ => {
+                        return Err(UsageError::new(format!(
+                            "Option '{}' must be sensitive or insensitive, not '{}'",
+                            name, value
+                        )))
+                    }
+                }
+            }
+            "--alphabet" => self.alphabet = Some(value.to_owned()),
+            "--metric" => {
+                self.metric = match value {
+                    "hamming" => Metric::Hamming,
+                    "levenshtein" => Metric::Levenshtein,
+                    _ => {
+                        return Err(UsageError::new(format!(
+                            "Option '{}' must be hamming or levenshtein, not '{}'",
+                            name, value
+                        )))
+                    }
+                }
+            }
+            "--distance" => self.distance = positive(name, value)?,
+            "--clusters" => self.clusters = Some(positive(name, value)?),
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+impl Scanner {
+    /// The letter a unit is counted as
+    fn fold(&self, unit: &str) -> String {
+        if self.ignore_case {
+            unit.to_lowercase()
+        } else {
+            unit.to_owned()
+        }
+    }
+
+    /// The letters counted, `None` if all are
+    fn letters(&self) -> Option<HashSet<String>> {
+        self.alphabet.as_ref().map(|alphabet| {
+            self.units
+                .split(&self.units.normalize(alphabet))
+                .into_iter()
+                .map(|unit| self.fold(unit))
+                .collect()
+        })
+    }
+}
+
+/// What the IDs are made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    /// Unicode scalar values
+    Chars,
+    /// User-perceived characters, a character with the marks combined with it
+    Graphemes,
+}
+
+impl Units {
+    /// The ID in the form it is split in: graphemes are composed to Unicode
+    /// normalization form C, so a precomposed "é" equals an "e" with a combining accent.
+    pub fn normalize(self, id: &str) -> Cow<'_, str> {
+        match self {
+            Units::Graphemes if !is_nfc(id) => Cow::Owned(id.nfc().collect()),
+            _ => Cow::Borrowed(id),
+        }
+    }
+
+    /// Split the ID into its units, the extended grapheme clusters of Unicode
+    /// Standard Annex #29 for graphemes. The ID is split as it is, normalize it first.
+    pub fn split(self, id: &str) -> Vec<&str> {
+        match self {
+            Units::Chars => id
+                .char_indices()
+                .map(|(start, c)| &id[start..start + c.len_utf8()])
+                .collect(),
+            Units::Graphemes => id.graphemes(true).collect(),
+        }
+    }
+}
+
+/// IDs the close pairs cannot be found for as expected
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Anomalies {
+    /// Indices of the empty IDs, which are never close to another one
+    pub empty: Vec<usize>,
+    /// Indices of the IDs not as long as most IDs, which have no substitutions
+    /// with most IDs
+    pub odd_lengths: Vec<usize>,
+}
+
+impl Anomalies {
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        let mut details = Vec::new();
+        if !self.empty.is_empty() {
+            details.push(("empty", Json::from(self.empty.clone())));
+        }
+        if !self.odd_lengths.is_empty() {
+            details.push(("odd_lengths", Json::from(self.odd_lengths.clone())));
+        }
+        details
+    }
+}
+
+/// The empty IDs, and the IDs of another length than most, counted in units.
+pub fn anomalies<S: AsRef<str>>(ids: &[S], units: Units) -> Anomalies {
+    let lengths: Vec<usize> = ids
+        .iter()
+        .map(|id| units.split(&units.normalize(id.as_ref())).len())
+        .collect();
+    let mut frequencies: HashMap<usize, usize> = HashMap::new();
+    for length in lengths.iter().filter(|length| **length > 0) {
+        *frequencies.entry(*length).or_insert(0) += 1;
+    }
+    let usual = frequencies
+        .iter()
+        .max_by_key(|(length, count)| (**count, Reverse(**length)))
+        .map(|(length, _)| *length);
+    let indices = |odd: &dyn Fn(usize) -> bool| -> Vec<usize> {
+        (0..lengths.len())
+            .filter(|index| odd(lengths[*index]))
+            .collect()
+    };
+    Anomalies {
+        empty: indices(&|length| length == 0),
+        odd_lengths: indices(&|length| length > 0 && Some(length) != usual),
+    }
+}
+
+/// How far apart two IDs are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// The number of substitutions, only IDs of equal length are compared
+    Hamming,
+    /// The number of substitutions, insertions and deletions
+    Levenshtein,
+}
+
+impl Generate for Day02 {
+    const SIZE: usize = 250;
+
+    /// `size` box IDs of 26 random lowercase letters, but at least 2.
+    ///
+    /// A single pair of IDs differs in one letter, any two random IDs
+    /// differing in at most one letter is vanishingly unlikely.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+        let random_id = |rng: &mut Rng| -> Vec<u8> {
+            (0..LETTERS.len()).map(|_| *rng.choose(LETTERS)).collect()
+        };
+
+        let mut ids: Vec<_> = (0..size.max(2) - 1).map(|_| random_id(rng)).collect();
+        let mut similar = rng.choose(&ids).clone();
+        let position = rng.below(similar.len());
+        let original = similar[position];
+        while similar[position] == original {
+            similar[position] = *rng.choose(LETTERS);
+        }
+        ids.push(similar);
+        rng.shuffle(&mut ids);
+
+        ids.iter()
+            .map(|id| format!("{}\n", String::from_utf8_lossy(id)))
+            .collect()
+    }
+}
+
+/// The number of IDs containing some letter exactly as often as each multiplicity.
+/// The answer is their product.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    /// The multiplicities in ascending order, each with the number of IDs
+    pub counts: Vec<(usize, usize)>,
+    pub anomalies: Anomalies,
+}
+
+impl Checksum {
+    /// The product of the counts, `None` if it overflows
+    pub fn value(&self) -> Option<usize> {
+        self.counts
+            .iter()
+            .try_fold(1usize, |product, (_, count)| product.checked_mul(*count))
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value() {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "overflow"),
+        }
+    }
+}
+
+impl Answer for Checksum {
+    fn value(&self) -> Json {
+        Json::from(Checksum::value(self))
+    }
+
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        let counts = self
+            .counts
+            .iter()
+            .map(|(multiplicity, count)| (multiplicity.to_string(), Json::from(*count)));
+        let mut details = vec![("counts", Json::object(counts))];
+        details.extend(self.anomalies.details());
+        details
+    }
+}
+
+/// An edit turning the first ID of a pair into the second,
+/// at a position of a unit of the first ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Edit {
+    Substitution(usize),
+    /// A unit of the second ID is inserted before the position
+    Insertion(usize),
+    Deletion(usize),
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Edit::Substitution(position) => write!(f, "substitution at {}", position),
+            Edit::Insertion(position) => write!(f, "insertion at {}", position),
+            Edit::Deletion(position) => write!(f, "deletion at {}", position),
+        }
+    }
+}
+
+impl From<Edit> for Json {
+    fn from(edit: Edit) -> Self {
+        Json::from(edit.to_string())
+    }
+}
+
+/// Two different IDs close to each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosePair {
+    /// Index of the first ID in the list
+    pub first: usize,
+    /// Index of the second ID in the list, after the first
+    pub second: usize,
+    /// The edits of an alignment of the IDs, ordered by position
+    pub edits: Vec<Edit>,
+    /// The units the alignment keeps
+    pub common: String,
+}
+
+impl From<ClosePair> for Json {
+    fn from(pair: ClosePair) -> Self {
+        Json::object(vec![
+            ("first", Json::from(pair.first)),
+            ("second", Json::from(pair.second)),
+            ("edits", Json::from(pair.edits)),
+            ("common", Json::from(pair.common)),
+        ])
+    }
+}
+
+/// All pairs of close IDs, at least one. The answer is the common letters of the first pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosePairs {
+    pub pairs: Vec<ClosePair>,
+    pub anomalies: Anomalies,
+    /// The clusters asked for, the largest first
+    pub clusters: Vec<Cluster>,
+}
+
+impl fmt::Display for ClosePairs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.pairs[0].common)
+    }
+}
+
+impl Answer for ClosePairs {
+    fn value(&self) -> Json {
+        Json::from(self.pairs[0].common.as_str())
+    }
+
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        let mut details = vec![
+            ("first", Json::from(self.pairs[0].first)),
+            ("second", Json::from(self.pairs[0].second)),
+            ("edits", Json::from(self.pairs[0].edits.clone())),
+        ];
+        if self.pairs.len() > 1 {
+            details.push(("pairs", Json::from(self.pairs.clone())));
+        }
+        details.extend(self.anomalies.details());
+        if !self.clusters.is_empty() {
+            details.push(("clusters", Json::from(self.clusters.clone())));
+        }
+        details
+    }
+}
+
+/// Everything that can go wrong on day 2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// No two box IDs are close to each other
+    NoCommonIds,
+    /// The product of the counts does not fit into an unsigned integer
+    ChecksumOverflow,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::NoCommonIds => write!(f, "No two box ids are close to each other"),
+            PuzzleError::ChecksumOverflow => write!(f, "The checksum is too large"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {}
+
+/// Multiply the number of IDs containing some letter exactly twice
+/// with the number of IDs containing some letter exactly three times,
+/// `None` if the product overflows, which needs more than about 2^32 IDs on 64 bits.
+pub fn list_checksum<S: AsRef<str>>(ids: &[S]) -> Option<usize> {
+    checksum(ids, &Scanner::default()).value()
+}
+
+/// Count the IDs containing some letter exactly as often as each multiplicity of the scanner.
+pub fn checksum<S: AsRef<str>>(ids: &[S], scanner: &Scanner) -> Checksum {
+    let letters = scanner.letters();
+    let mut counts: Vec<(usize, usize)> = scanner
+        .multiplicities
+        .iter()
+        .map(|multiplicity| (*multiplicity, 0))
+        .collect();
+    for id in ids {
+        let multiplicities: BTreeSet<usize> = count_letters(id.as_ref(), scanner, &letters)
+            .values()
+            .cloned()
+            .collect();
+        for (multiplicity, count) in &mut counts {
+            if multiplicities.contains(multiplicity) {
+                *count += 1;
+            }
+        }
+    }
+    Checksum {
+        counts,
+        anomalies: anomalies(ids, scanner.units),
+    }
+}
+
+/// How often each letter appears in the ID, as counted by the scanner.
+pub fn letter_counts(id: &str, scanner: &Scanner) -> HashMap<String, usize> {
+    count_letters(id, scanner, &scanner.letters())
+}
+
+fn count_letters(
+    id: &str,
+    scanner: &Scanner,
+    letters: &Option<HashSet<String>>,
+) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for unit in scanner.units.split(&scanner.units.normalize(id)) {
+        let letter = scanner.fold(unit);
+        if letters
+            .as_ref()
+            .is_none_or(|letters| letters.contains(&letter))
+        {
+            *counts.entry(letter).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// The letters two IDs of equal length have in common,
+/// for the first pair of IDs differing in exactly one position.
+pub fn common_box_id_letters<S: AsRef<str>>(ids: &[S]) -> Result<String, PuzzleError> {
+    close_pairs(ids)
+        .into_iter()
+        .next()
+        .map(|pair| pair.common)
+        .ok_or(PuzzleError::NoCommonIds)
+}
+
+/// All pairs of IDs differing in exactly one character, ordered by their indices.
+pub fn close_pairs<S: AsRef<str>>(ids: &[S]) -> Vec<ClosePair> {
+    close_pairs_within(ids, &Scanner::default())
+}
+
+/// All pairs of different IDs at most `distance` apart by the metric of the scanner,
+/// ordered by their indices. Empty IDs are never close to another ID.
+///
+/// A single substitution is found with `substitutions`, otherwise the IDs are ordered by
+/// length and each is aligned with those not differing too much in length.
+pub fn close_pairs_within<S: AsRef<str>>(ids: &[S], scanner: &Scanner) -> Vec<ClosePair> {
+    let ids: Vec<Cow<str>> = ids
+        .iter()
+        .map(|id| scanner.units.normalize(id.as_ref()))
+        .collect();
+    let ids: Vec<Vec<&str>> = ids.iter().map(|id| scanner.units.split(id)).collect();
+    let (metric, distance) = (scanner.metric, scanner.distance);
+    if metric == Metric::Hamming && distance == 1 {
+        return substitutions(&ids);
+    }
+    let length_slack = match metric {
+        Metric::Hamming => 0,
+        Metric::Levenshtein => distance,
+    };
+    let mut by_length: Vec<usize> = (0..ids.len())
+        .filter(|index| !ids[*index].is_empty())
+        .collect();
+    by_length.sort_by_key(|index| ids[*index].len());
+
+    let mut pairs = Vec::new();
+    for (i, a) in by_length.iter().enumerate() {
+        for b in &by_length[i + 1..] {
+            if ids[*b].len() > ids[*a].len() + length_slack {
+                break;
+            }
+            let (first, second) = (*a.min(b), *a.max(b));
+            if let Some(edits) = align(&ids[first], &ids[second], metric, distance) {
+                let common = kept(&ids[first], &edits);
+                pairs.push(ClosePair {
+                    first,
+                    second,
+                    edits,
+                    common,
+                });
+            }
+        }
+    }
+    pairs.sort_by_key(|pair| (pair.first, pair.second));
+    pairs
+}
+
+/// All pairs of IDs differing in exactly one unit, ordered by their indices.
+///
+/// Each ID is put into a bucket for every position, keyed by the position and the ID
+/// without the unit there. Two IDs share a bucket exactly if they are equal apart
+/// from that position, so only IDs sharing a bucket are compared.
+fn substitutions(ids: &[Vec<&str>]) -> Vec<ClosePair> {
+    let mut buckets: HashMap<(usize, Vec<&str>), Vec<usize>> = HashMap::new();
+    for (index, id) in ids.iter().enumerate() {
+        for position in 0..id.len() {
+            let masked = masked(id, position);
+            buckets.entry((position, masked)).or_default().push(index);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for ((position, common), indices) in buckets {
+        for (i, first) in indices.iter().enumerate() {
+            for second in &indices[i + 1..] {
+                // Equal IDs share every bucket, but do not differ at all
+                if ids[*first][position] != ids[*second][position] {
+                    pairs.push(ClosePair {
+                        first: *first,
+                        second: *second,
+                        edits: vec![Edit::Substitution(position)],
+                        common: common.concat(),
+                    });
+                }
+            }
+        }
+    }
+    pairs.sort_by_key(|pair| (pair.first, pair.second));
+    pairs
+}
+
+/// The edits of an alignment of two different IDs at most `distance` apart,
+/// `None` if they are equal or further apart.
+pub fn align<T: PartialEq>(
+    first: &[T],
+    second: &[T],
+    metric: Metric,
+    distance: usize,
+) -> Option<Vec<Edit>> {
+    let edits = match metric {
+        Metric::Hamming if first.len() == second.len() => (0..first.len())
+            .filter(|position| first[*position] != second[*position])
+            .map(Edit::Substitution)
+            .collect(),
+        Metric::Hamming => return None,
+        Metric::Levenshtein => levenshtein(first, second, distance)?,
+    };
+    if edits.is_empty() || edits.len() > distance {
+        None
+    } else {
+        Some(edits)
+    }
+}
+
+/// The edits of an alignment with the fewest edits, `None` if there are more than `limit`.
+///
+/// Ties prefer keeping a unit over substituting it over deleting it.
+fn levenshtein<T: PartialEq>(first: &[T], second: &[T], limit: usize) -> Option<Vec<Edit>> {
+    let (rows, columns) = (first.len(), second.len());
+    if rows.max(columns) - rows.min(columns) > limit {
+        return None;
+    }
+    // distances[i][j]: the number of edits turning first[..i] into second[..j]
+    let mut distances = vec![vec![0; columns + 1]; rows + 1];
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=rows {
+        distances[i][0] = i;
+        for j in 1..=columns {
+            let substitution = usize::from(first[i - 1] != second[j - 1]);
+            distances[i][j] = (distances[i - 1][j - 1] + substitution)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+        }
+        if distances[i].iter().all(|distance| *distance > limit) {
+            return None;
+        }
+    }
+    if distances[rows][columns] > limit {
+        return None;
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (rows, columns);
+    while i > 0 || j > 0 {
+        let here = distances[i][j];
+        if i > 0 && j > 0 && first[i - 1] == second[j - 1] && here == distances[i - 1][j - 1] {
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && here == distances[i - 1][j - 1] + 1 {
+            i -= 1;
+            j -= 1;
+            edits.push(Edit::Substitution(i));
+        } else if i > 0 && here == distances[i - 1][j] + 1 {
+            i -= 1;
+            edits.push(Edit::Deletion(i));
+        } else {
+            j -= 1;
+            edits.push(Edit::Insertion(i));
+        }
+    }
+    edits.reverse();
+    Some(edits)
+}
+
+/// The units of the first ID kept by the edits
+fn kept<T: fmt::Display>(first: &[T], edits: &[Edit]) -> String {
+    let removed: BTreeSet<usize> = edits
+        .iter()
+        .filter_map(|edit| match edit {
+            Edit::Substitution(position) | Edit::Deletion(position) => Some(*position),
+            Edit::Insertion(_) => None,
+        })
+        .collect();
+    first
+        .iter()
+        .enumerate()
+        .filter(|(position, _)| !removed.contains(position))
+        .map(|(_, unit)| unit.to_string())
+        .collect()
+}
+
+/// IDs linked by chains of close IDs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// Indices of the IDs in the list, in ascending order
+    pub members: Vec<usize>,
+    /// The most common unit at each position of the alignment with the most central ID
+    pub consensus: String,
+    /// The positions of the units of the consensus not all members agree on, in ascending
+    /// order. Units inserted by some members vary at the unit following them, which is
+    /// the length of the consensus for units inserted at the end.
+    pub varying: Vec<usize>,
+}
+
+impl Cluster {
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+impl From<Cluster> for Json {
+    fn from(cluster: Cluster) -> Self {
+        Json::object(vec![
+            ("size", Json::from(cluster.size())),
+            ("members", Json::from(cluster.members)),
+            ("consensus", Json::from(cluster.consensus)),
+            ("varying", Json::from(cluster.varying)),
+        ])
+    }
+}
+
+/// Group the IDs into clusters of close IDs by the scanner, or linked by a chain of
+/// close IDs. Equal IDs are always in the same cluster.
+///
+/// Every ID is in exactly one cluster, the largest clusters come first.
+pub fn clusters<S: AsRef<str>>(ids: &[S], scanner: &Scanner) -> Vec<Cluster> {
+    let normalized: Vec<Cow<str>> = ids
+        .iter()
+        .map(|id| scanner.units.normalize(id.as_ref()))
+        .collect();
+    let mut sets = DisjointSets::new(ids.len());
+    let mut first_of: HashMap<&str, usize> = HashMap::new();
+    for (index, id) in normalized.iter().enumerate() {
+        let first = *first_of.entry(id.as_ref()).or_insert(index);
+        sets.union(first, index);
+    }
+    for pair in close_pairs_within(ids, scanner) {
+        sets.union(pair.first, pair.second);
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..ids.len() {
+        members.entry(sets.find(index)).or_default().push(index);
+    }
+    let ids: Vec<Vec<&str>> = normalized
+        .iter()
+        .map(|id| scanner.units.split(id))
+        .collect();
+    let mut clusters: Vec<Cluster> = members
+        .into_values()
+        .map(|members| consensus(&ids, members, scanner.metric))
+        .collect();
+    clusters.sort_by_key(|cluster| (Reverse(cluster.size()), cluster.members[0]));
+    clusters
+}
+
+/// The cluster of the members, aligned with the member closest to all others.
+///
+/// Each member votes at every position of the central ID for the unit aligned with it,
+/// or for none if it is deleted, and before every position and at the end for the units
+/// it inserts there. Ties are won by the central ID.
+fn consensus(ids: &[Vec<&str>], members: Vec<usize>, metric: Metric) -> Cluster {
+    // Hamming clusters have IDs of one length, so every alignment is complete
+    let alignment = |first: usize, second: usize| {
+        align(&ids[first], &ids[second], metric, usize::MAX).unwrap_or_default()
+    };
+    let center = *members
+        .iter()
+        .min_by_key(|member| {
+            members
+                .iter()
+                .map(|other| alignment(**member, *other).len())
+                .sum::<usize>()
+        })
+        .expect("A cluster has members");
+
+    let length = ids[center].len();
+    let mut votes: Vec<HashMap<Option<&str>, usize>> = vec![HashMap::new(); length];
+    // The units inserted before each position, and at the end
+    let mut gaps: Vec<HashMap<Vec<&str>, usize>> = vec![HashMap::new(); length + 1];
+    for member in &members {
+        let mut aligned: Vec<Option<&str>> = ids[center].iter().cloned().map(Some).collect();
+        let mut inserted: Vec<Vec<&str>> = vec![Vec::new(); length + 1];
+        // The number of units inserted minus those deleted before the edit
+        let mut offset = 0isize;
+        for edit in alignment(center, *member) {
+            match edit {
+                Edit::Substitution(position) => {
+                    aligned[position] = Some(ids[*member][(position as isize + offset) as usize])
+                }
+                Edit::Insertion(position) => {
+                    inserted[position].push(ids[*member][(position as isize + offset) as usize]);
+                    offset += 1;
+                }
+                Edit::Deletion(position) => {
+                    aligned[position] = None;
+                    offset -= 1;
+                }
+            }
+        }
+        for (position, vote) in aligned.into_iter().enumerate() {
+            *votes[position].entry(vote).or_insert(0) += 1;
+        }
+        for (position, units) in inserted.into_iter().enumerate() {
+            *gaps[position].entry(units).or_insert(0) += 1;
+        }
+    }
+
+    let mut consensus = String::new();
+    let mut units = 0;
+    let mut varying = BTreeSet::new();
+    for (position, gap) in gaps.iter().enumerate() {
+        let inserted = gap
+            .iter()
+            .max_by_key(|(units, count)| (**count, units.is_empty(), Reverse((*units).clone())))
+            .map(|(units, _)| units.clone())
+            .unwrap_or_default();
+        if gap.len() > 1 {
+            // An insertion varies at the units inserted, or else at the unit following it
+            varying.extend(units..units + inserted.len().max(1));
+        }
+        for unit in &inserted {
+            consensus.push_str(unit);
+            units += 1;
+        }
+
+        let votes = match votes.get(position) {
+            Some(votes) => votes,
+            None => break,
+        };
+        let own = Some(ids[center][position]);
+        let winner = votes
+            .iter()
+            .max_by_key(|(vote, count)| (**count, **vote == own, Reverse(**vote)))
+            .map(|(vote, _)| *vote)
+            .unwrap_or(own);
+        if let Some(unit) = winner {
+            if votes.len() > 1 {
+                varying.insert(units);
+            }
+            consensus.push_str(unit);
+            units += 1;
+        }
+    }
+    let varying = varying.into_iter().collect();
+    Cluster {
+        members,
+        consensus,
+        varying,
+    }
+}
+
+/// A partition of `0..len` into sets, merged by `union`
+struct DisjointSets {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        DisjointSets {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    /// The representative of the set containing `element`
+    fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            self.parents[element] = self.parents[self.parents[element]];
+            element = self.parents[element];
+        }
+        element
+    }
+
+    /// Merge the sets of `a` and `b`, the larger set keeps its representative
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+    }
+}
+
+/// The units of the ID except the one at `position`
+fn masked<'a>(id: &[&'a str], position: usize) -> Vec<&'a str> {
+    id[..position]
+        .iter()
+        .chain(&id[position + 1..])
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_example() {
+    let input = vec![
+        "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab",
+    ];
+    let output = list_checksum(&input);
+    assert_eq!(output, Some(12))
+}
+
+#[test]
+fn test_multiplicities() {
+    let input = vec![
+        "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab", "aaaab",
+    ];
+    let scanner = Scanner {
+        multiplicities: [2, 3, 4].iter().cloned().collect(),
+        ..Scanner::default()
+    };
+
+    let checksum = checksum(&input, &scanner);
+
+    assert_eq!(vec![(2, 4), (3, 3), (4, 1)], checksum.counts);
+    assert_eq!(Some(12), checksum.value());
+}
+
+#[test]
+fn test_case_and_alphabet() {
+    let input = vec!["aAb", "AAbb", "ab--"];
+    let insensitive = Scanner::from_options(&[
+        ("--case".to_owned(), "insensitive".to_owned()),
+        ("--multiplicities".to_owned(), "2".to_owned()),
+    ])
+    .unwrap();
+    let sensitive = Scanner {
+        ignore_case: false,
+        ..insensitive.clone()
+    };
+    let alphabet = Scanner {
+        alphabet: Some("AB".chars().collect()),
+        ..insensitive.clone()
+    };
+
+    assert_eq!(vec![(2, 3)], checksum(&input, &insensitive).counts);
+    assert_eq!(vec![(2, 2)], checksum(&input, &sensitive).counts);
+    assert_eq!(vec![(2, 2)], checksum(&input, &alphabet).counts);
+    assert_eq!(Some(&2), letter_counts("aAb", &alphabet).get("a"));
+    assert_eq!(None, letter_counts("ab--", &alphabet).get("-"));
+}
+
+#[test]
+fn test_common_example() {
+    let input = vec![
+        "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+    ];
+    let result = common_box_id_letters(&input);
+
+    let output = result.unwrap();
+    assert_eq!(output, "fgij")
+}
+
+#[test]
+fn test_close_pairs() {
+    let input = vec!["abcde", "abxde", "abcde", "xbcde", "abcd", "abxdy"];
+
+    let pairs = close_pairs(&input);
+
+    let found: Vec<_> = pairs
+        .iter()
+        .map(|pair| (pair.first, pair.second, pair.edits[0], pair.common.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (0, 1, Edit::Substitution(2), "abde"),
+            (0, 3, Edit::Substitution(0), "bcde"),
+            (1, 2, Edit::Substitution(2), "abde"),
+            (1, 5, Edit::Substitution(4), "abxd"),
+            (2, 3, Edit::Substitution(0), "bcde"),
+        ],
+        found
+    );
+    assert!(close_pairs(&["abc", "abc", "ab"]).is_empty());
+}
+
+#[test]
+fn test_close_pairs_match_all_pairs() {
+    aoc_common::check::check(
+        |choices| choices.vec(0..=12, |choices| choices.string(0..=4, "abc")),
+        |ids| {
+            let mut expected = Vec::new();
+            for first in 0..ids.len() {
+                for second in first + 1..ids.len() {
+                    let (a, b): (Vec<char>, Vec<char>) =
+                        (ids[first].chars().collect(), ids[second].chars().collect());
+                    let differing: Vec<usize> = (0..a.len().min(b.len()))
+                        .filter(|position| a[*position] != b[*position])
+                        .collect();
+                    if a.len() == b.len() && differing.len() == 1 {
+                        expected.push((first, second, vec![Edit::Substitution(differing[0])]));
+                    }
+                }
+            }
+            let found: Vec<_> = close_pairs(ids)
+                .into_iter()
+                .map(|pair| (pair.first, pair.second, pair.edits))
+                .collect();
+            if found == expected {
+                Ok(())
+            } else {
+                Err(format!("found {:?} instead of {:?}", found, expected))
+            }
+        },
+    );
+}
+
+#[test]
+fn test_levenshtein_pairs() {
+    let input = vec!["abcde", "abde", "xabcde", "abxdy", "axcyez"];
+
+    let found: Vec<_> = close_pairs_within(
+        &input,
+        &Scanner {
+            metric: Metric::Levenshtein,
+            distance: 1,
+            ..Scanner::default()
+        },
+    )
+    .into_iter()
+    .map(|pair| (pair.first, pair.second, pair.edits, pair.common))
+    .collect();
+    assert_eq!(
+        vec![
+            (0, 1, vec![Edit::Deletion(2)], "abde".to_owned()),
+            (0, 2, vec![Edit::Insertion(0)], "abcde".to_owned()),
+        ],
+        found
+    );
+
+    let far = close_pairs_within(
+        &input,
+        &Scanner {
+            metric: Metric::Hamming,
+            distance: 2,
+            ..Scanner::default()
+        },
+    );
+    assert_eq!(
+        vec![(0, 3, "abd".to_owned())],
+        far.into_iter()
+            .map(|pair| (pair.first, pair.second, pair.common))
+            .collect::<Vec<_>>()
+    );
+    let scanner = Scanner::from_options(&[
+        ("--metric".to_owned(), "levenshtein".to_owned()),
+        ("--distance".to_owned(), "3".to_owned()),
+    ])
+    .unwrap();
+    let answer = Day02::part2(&vec!["axcyez".to_owned(), "abcde".to_owned()], &scanner);
+    assert_eq!("ace", answer.unwrap().to_string());
+}
+
+#[test]
+fn test_levenshtein_alignment() {
+    // The Levenshtein distance without alignment
+    fn distance(a: &[char], b: &[char]) -> usize {
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, x) in a.iter().enumerate() {
+            let mut previous = row.clone();
+            previous[0] = i + 1;
+            for (j, y) in b.iter().enumerate() {
+                previous[j + 1] = (row[j] + usize::from(x != y))
+                    .min(row[j + 1] + 1)
+                    .min(previous[j] + 1);
+            }
+            row = previous;
+        }
+        row[b.len()]
+    }
+    fn is_subsequence(common: &str, id: &[char]) -> bool {
+        let mut rest = id.iter();
+        common.chars().all(|c| rest.any(|other| *other == c))
+    }
+
+    aoc_common::check::check(
+        |choices| {
+            (
+                choices.string(0..=6, "abc").chars().collect::<Vec<_>>(),
+                choices.string(0..=6, "abc").chars().collect::<Vec<_>>(),
+            )
+        },
+        |(a, b)| {
+            let expected = distance(a, b);
+            let edits = align(a, b, Metric::Levenshtein, 6).unwrap_or_default();
+            let common = kept(a, &edits);
+            if edits.len() != expected {
+                Err(format!("{:?} instead of {} edits", edits, expected))
+            } else if !is_subsequence(&common, a) || !is_subsequence(&common, b) {
+                Err(format!("'{}' is not common", common))
+            } else if common.chars().count() + edits.len() < a.len().max(b.len()) {
+                Err(format!("'{}' is too short for {:?}", common, edits))
+            } else {
+                Ok(())
+            }
+        },
+    );
+}
+
+#[test]
+fn test_clusters() {
+    let input = vec![
+        "abcde", "zzzzz", "abxde", "abcde", "abcdy", "qqqqq", "zzzzy", "abde",
+    ];
+
+    let hamming = clusters(
+        &input,
+        &Scanner {
+            metric: Metric::Hamming,
+            distance: 1,
+            ..Scanner::default()
+        },
+    );
+
+    let found: Vec<_> = hamming
+        .iter()
+        .map(|cluster| {
+            (
+                cluster.members.clone(),
+                cluster.consensus.as_str(),
+                cluster.varying.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (vec![0, 2, 3, 4], "abcde", vec![2, 4]),
+            (vec![1, 6], "zzzzz", vec![4]),
+            (vec![5], "qqqqq", vec![]),
+            (vec![7], "abde", vec![]),
+        ],
+        found
+    );
+
+    let levenshtein = clusters(
+        &input,
+        &Scanner {
+            metric: Metric::Levenshtein,
+            distance: 1,
+            ..Scanner::default()
+        },
+    );
+    assert_eq!(vec![0, 2, 3, 4, 7], levenshtein[0].members);
+    assert_eq!("abcde", levenshtein[0].consensus);
+    assert_eq!(vec![2, 4], levenshtein[0].varying);
+    assert_eq!(3, levenshtein.len());
+}
+
+#[test]
+fn test_clusters_partition_ids() {
+    aoc_common::check::check(
+        |choices| {
+            let ids = choices.vec(0..=10, |choices| choices.string(0..=4, "ab"));
+            (ids, choices.range(1..=2) as usize)
+        },
+        |(ids, distance)| {
+            let clusters = clusters(
+                ids,
+                &Scanner {
+                    metric: Metric::Levenshtein,
+                    distance: *distance,
+                    ..Scanner::default()
+                },
+            );
+            let mut members: Vec<usize> = clusters
+                .iter()
+                .flat_map(|cluster| cluster.members.clone())
+                .collect();
+            members.sort();
+            let pairs = close_pairs_within(
+                ids,
+                &Scanner {
+                    metric: Metric::Levenshtein,
+                    distance: *distance,
+                    ..Scanner::default()
+                },
+            );
+            let cluster_of = |index: usize| {
+                clusters
+                    .iter()
+                    .position(|cluster| cluster.members.contains(&index))
+            };
+            if members != (0..ids.len()).collect::<Vec<_>>() {
+                Err(format!("{:?} are not the indices of the ids", members))
+            } else if let Some(pair) = pairs
+                .iter()
+                .find(|pair| cluster_of(pair.first) != cluster_of(pair.second))
+            {
+                Err(format!("{:?} is split", pair))
+            } else {
+                Ok(())
+            }
+        },
+    );
+}
+
+#[test]
+fn test_graphemes() {
+    let flags = "\u{1F1E9}\u{1F1EA}\u{1F1EB}\u{1F1F7}";
+    assert_eq!(
+        vec!["\u{1F1E9}\u{1F1EA}", "\u{1F1EB}\u{1F1F7}"],
+        Units::Graphemes.split(flags)
+    );
+    let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}x";
+    assert_eq!(2, Units::Graphemes.split(family).len());
+    assert_eq!(
+        vec!["e\u{301}", "t", "e\u{301}", "\r\n"],
+        Units::Graphemes.split("e\u{301}te\u{301}\r\n")
+    );
+    assert_eq!(5, Units::Chars.split("e\u{301}te\u{301}").len());
+    assert!(Units::Graphemes.split("").is_empty());
+    // Hangul jamo form one syllable, a spacing vowel sign joins its Thai consonant
+    assert_eq!(1, Units::Graphemes.split("\u{1100}\u{1161}\u{11A8}").len());
+    assert_eq!(1, Units::Graphemes.split("\u{0E01}\u{0E33}").len());
+    assert_eq!(
+        "\u{AC01}",
+        Units::Graphemes.normalize("\u{1100}\u{1161}\u{11A8}")
+    );
+    assert_eq!("cafe\u{301}", Units::Chars.normalize("cafe\u{301}"));
+}
+
+#[test]
+fn test_unicode_ids() {
+    // "é" with a combining accent, against "è" with another one, and precomposed "é",
+    // the same grapheme as the first once normalized
+    let input = vec!["cafe\u{301}", "cafe\u{300}", "caf\u{e9}", "", "x"];
+    let graphemes = Scanner {
+        units: Units::Graphemes,
+        ..Scanner::default()
+    };
+
+    let pairs = close_pairs_within(&input, &graphemes);
+
+    let found: Vec<_> = pairs
+        .iter()
+        .map(|pair| (pair.first, pair.second, pair.common.as_str()))
+        .collect();
+    assert_eq!(vec![(0, 1, "caf"), (1, 2, "caf")], found);
+    // Counting chars, the combining accents differ in the fifth of five chars
+    let chars: Vec<_> = close_pairs(&input)
+        .into_iter()
+        .map(|pair| (pair.first, pair.second, pair.common))
+        .collect();
+    assert_eq!(vec![(0, 1, "cafe".to_owned())], chars);
+    assert_eq!(
+        Err(PuzzleError::NoCommonIds),
+        common_box_id_letters(&["", ""])
+    );
+
+    let answer = Day02::part2(&input.iter().map(|id| id.to_string()).collect(), &graphemes);
+    assert_eq!(
+        Anomalies {
+            empty: vec![3],
+            odd_lengths: vec![4],
+        },
+        answer.unwrap().anomalies
+    );
+    let counts = letter_counts("e\u{301}e\u{301}e", &graphemes);
+    assert_eq!(Some(&2), counts.get("\u{e9}"));
+    assert_eq!(Some(&1), counts.get("e"));
+}
+
+#[test]
+fn test_clusters_of_normalized_ids() {
+    let input = ["cafe\u{301}", "caf\u{e9}", "tea"];
+    let graphemes = Scanner {
+        units: Units::Graphemes,
+        ..Scanner::default()
+    };
+
+    let clusters = clusters(&input, &graphemes);
+
+    assert_eq!(vec![0, 1], clusters[0].members);
+    assert_eq!("caf\u{e9}", clusters[0].consensus);
+    assert!(clusters[0].varying.is_empty());
+    assert_eq!(vec![2], clusters[1].members);
+}
+
+#[test]
+fn test_cluster_insertions() {
+    let input = ["abcde", "abcdex", "abcdey", "zabcde"];
+    let scanner = Scanner::from_options(&[
+        ("--metric".to_owned(), "levenshtein".to_owned()),
+        ("--clusters".to_owned(), "2".to_owned()),
+    ])
+    .unwrap();
+
+    let clusters = clusters(&input[..3], &scanner);
+
+    assert_eq!(1, clusters.len());
+    assert_eq!("abcde", clusters[0].consensus);
+    assert_eq!(vec![5], clusters[0].varying);
+
+    let ids: Vec<String> = input.iter().map(|id| id.to_string()).collect();
+    let answer = Day02::part2(&ids, &scanner).unwrap();
+    assert_eq!(
+        vec![Cluster {
+            members: vec![0, 1, 2, 3],
+            consensus: "abcde".to_owned(),
+            varying: vec![0, 5],
+        }],
+        answer.clusters
+    );
+    assert!(answer.details().iter().any(|(name, _)| *name == "clusters"));
+}
@@ -0,0 +1,1368 @@
+//! Day 3: No Matter How You Slice It
+//!
+//! Claims rectangular patches of fabric on a grid, counts the square inches
+//! claimed more than once, and finds the only claim not overlapping any other.
+//! A sweep line over the claims does the same for fabric too large for a grid.
+
+#[macro_use]
+extern crate lazy_static;
+extern crate aoc_common;
+extern crate rayon;
+extern crate regex;
+
+use aoc_common::cli::UsageError;
+use aoc_common::diagnostic::{parse_lines, Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::input::{read_lines, StreamError};
+use aoc_common::params::{positive, unknown_option};
+use aoc_common::random::Rng;
+use aoc_common::render::{Color, Render};
+use aoc_common::{Grid2D, Params, Solution};
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::mem;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Solution of day 3 for the runners
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day3.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Vec<Patch>;
+    type Params = Fabric;
+    type Answer1 = u64;
+    type Answer2 = usize;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        parse_lines(input, FromStr::from_str).collect()
+    }
+
+    fn read<R: BufRead>(
+        reader: R,
+        _params: &Self::Params,
+    ) -> Result<Self::Input, StreamError<Self::Error>> {
+        read_patches(reader).collect()
+    }
+
+    fn part1(patches: &Self::Input, fabric: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        match fabric.engine {
+            Engine::Grid => Ok(claim_fabric(patches, fabric)?.count_overlapping() as u64),
+            Engine::Sweep => Ok(sweep(patches)?.overlapping),
+        }
+    }
+
+    fn part2(patches: &Self::Input, fabric: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let mut solo_claimed_patches = match fabric.engine {
+            Engine::Grid => claim_fabric(patches, fabric)?.solo_claimed_patches(),
+            Engine::Sweep => sweep(patches)?.solo,
+        };
+        solo_claimed_patches.sort_unstable();
+        match solo_claimed_patches.as_slice() {
+            [] => Err(PuzzleError::NoSoloClaim),
+            [solo] => Ok(*solo),
+            _ => Err(PuzzleError::SeveralSoloClaims(solo_claimed_patches)),
+        }
+    }
+}
+
+/// The size of the fabric in inches, 1000x1000 in the puzzle,
+/// and how the claims are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fabric {
+    pub size_x: usize,
+    pub size_y: usize,
+    pub bounds: Bounds,
+    pub engine: Engine,
+}
+
+impl Default for Fabric {
+    fn default() -> Self {
+        Fabric {
+            size_x: 1000,
+            size_y: 1000,
+            bounds: Bounds::Fixed,
+            engine: Engine::Grid,
+        }
+    }
+}
+
+/// The edges of the fabric claimed on the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bounds {
+    /// The size given, claims beyond it are an error
+    Fixed,
+    /// Just large enough for the claims
+    Auto,
+}
+
+/// How the overlapping claims are found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// Claim every square inch of a grid of the size of the fabric
+    Grid,
+    /// Sweep a line over the edges of the claims, the fabric is unbounded
+    Sweep,
+}
+
+impl Params for Fabric {
+    const USAGE: &'static str =
+        "[--width INCHES] [--height INCHES] [--bounds fixed|auto] [--engine grid|sweep]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--width" => self.size_x = positive(name, value)?,
+            "--height" => self.size_y = positive(name, value)?,
+            "--bounds" => {
+                self.bounds = match value {
+                    "fixed" => Bounds::Fixed,
+                    "auto" => Bounds::Auto,
+                    _ => {
+                        return Err(UsageError::new(format!(
+                            "Option '{}' must be fixed or auto, not '{}'",
+                            name, value
+                        )))
+                    }
+                }
+            }
+            "--engine" => {
+                self.engine = match value {
+                    "grid" => Engine::Grid,
+                    "sweep" => Engine::Sweep,
+                    _ => {
+                        return Err(UsageError::new(format!(
+                            "Option '{}' must be grid or sweep, not '{}'",
+                            name, value
+                        )))
+                    }
+                }
+            }
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+impl Generate for Day03 {
+    const SIZE: usize = 1300;
+
+    /// `size` claims of 10 to 29 inches on each side, all on the fabric of the puzzle, but at least 3.
+    ///
+    /// A single claim does not overlap any other, the others are placed in pairs
+    /// overlapping each other, and the last one overlaps its predecessor if `size` is even.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let fabric = Fabric::default();
+        let (fabric_x, fabric_y) = (fabric.size_x as u64, fabric.size_y as u64);
+        let below = |rng: &mut Rng, bound: u64| rng.below(bound as usize) as u64;
+        let random_size = |rng: &mut Rng| 10 + below(rng, 20);
+        let random_patch = |rng: &mut Rng| {
+            let (size_x, size_y) = (random_size(rng), random_size(rng));
+            Patch {
+                id: 0,
+                offset_x: below(rng, fabric_x - size_x + 1),
+                offset_y: below(rng, fabric_y - size_y + 1),
+                size_x,
+                size_y,
+            }
+        };
+        // Starts inside `patch`, moved back onto the fabric if needed, which keeps the overlap
+        let overlapping = |rng: &mut Rng, patch: &Patch| {
+            let (size_x, size_y) = (random_size(rng), random_size(rng));
+            Patch {
+                id: 0,
+                offset_x: (patch.offset_x + below(rng, patch.size_x)).min(fabric_x - size_x),
+                offset_y: (patch.offset_y + below(rng, patch.size_y)).min(fabric_y - size_y),
+                size_x,
+                size_y,
+            }
+        };
+
+        let solo = random_patch(rng);
+        let mut patches = vec![solo.clone()];
+        while patches.len() < size.max(3) {
+            let claims = if patches.len() + 1 == size.max(3) {
+                // Without a partner, the last claim overlaps the previous one
+                vec![overlapping(rng, &patches[patches.len() - 1])]
+            } else {
+                let first = random_patch(rng);
+                let second = overlapping(rng, &first);
+                vec![first, second]
+            };
+            if claims.iter().all(|patch| !patch.overlaps(&solo)) {
+                patches.extend(claims);
+            }
+        }
+        rng.shuffle(&mut patches);
+
+        patches
+            .into_iter()
+            .zip(1..)
+            .map(|(patch, id)| format!("{}\n", Patch { id, ..patch }))
+            .collect()
+    }
+}
+
+impl Render for Day03 {
+    /// A heat map of the fabric, unclaimed inches are black, inches claimed once are blue,
+    /// and overlapping inches grow hotter with the number of claims.
+    fn render(patches: &Self::Input, fabric: &Self::Params) -> Result<Grid2D<Color>, Self::Error> {
+        let grid = claim_fabric(patches, fabric)?;
+        let claims = grid.squares();
+        let most = claims.cells().iter().map(Vec::len).max().unwrap_or(0);
+        Ok(claims.map(|ids| match ids.len() {
+            0 => Color::BLACK,
+            1 => Color::rgb(40, 70, 120),
+            count => Color::heat(0.3 + 0.7 * (count - 1) as f64 / (most - 1) as f64),
+        }))
+    }
+}
+
+/// Everything that can go wrong on day 3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A line is not a claim
+    Parse { span: Span, expected: &'static str },
+    /// A claim reaches beyond the right or bottom edge of the fabric, by the inches given
+    OutOfBounds {
+        id: usize,
+        overrun_x: u128,
+        overrun_y: u128,
+    },
+    /// The fabric is too large for a grid
+    FabricTooLarge { size_x: u128, size_y: u128 },
+    /// The overlapping area does not fit into 64 bits
+    AreaOverflow,
+    /// Every claim overlaps some other claim
+    NoSoloClaim,
+    /// More than one claim does not overlap any other claim
+    SeveralSoloClaims(Vec<usize>),
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span, expected } => {
+                write!(f, "{}: expected {}, found '{}'", span, expected, span.text)
+            }
+            PuzzleError::OutOfBounds {
+                id,
+                overrun_x,
+                overrun_y,
+            } => {
+                write!(f, "Claim #{} reaches beyond the fabric by ", id)?;
+                match (overrun_x, overrun_y) {
+                    (x, 0) => write!(f, "{} inches to the right", x),
+                    (0, y) => write!(f, "{} inches at the bottom", y),
+                    (x, y) => write!(f, "{} inches to the right and {} at the bottom", x, y),
+                }
+            }
+            PuzzleError::FabricTooLarge { size_x, size_y } => write!(
+                f,
+                "A fabric of {}x{} inches is too large for a grid, try '--engine sweep'",
+                size_x, size_y
+            ),
+            PuzzleError::AreaOverflow => write!(f, "The overlapping area is too large"),
+            PuzzleError::NoSoloClaim => write!(f, "No non overlapping claim"),
+            PuzzleError::SeveralSoloClaims(ids) => {
+                write!(f, "More than one non overlapping claim: {:?}", ids)
+            }
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+}
+
+/// The claimed patches, parsed one line at a time while reading them.
+pub fn read_patches<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<Patch, StreamError<PuzzleError>>> {
+    read_lines(reader, FromStr::from_str)
+}
+
+/// Claim all patches on the fabric, or on a fabric just large enough for them.
+pub fn claim_fabric(patches: &[Patch], fabric: &Fabric) -> Result<Grid, PuzzleError> {
+    let mut grid = match fabric.bounds {
+        Bounds::Fixed => Grid::sized(fabric.size_x as u128, fabric.size_y as u128)?,
+        Bounds::Auto => Grid::fitting(patches)?,
+    };
+    for patch in patches {
+        grid.claim(patch.clone())?;
+    }
+    Ok(grid)
+}
+
+/// The most memory the squares of a grid may take, 4 GiB
+pub const MAX_GRID_BYTES: u128 = 1 << 32;
+
+/// Fabric of square inches, each remembering the ids of the patches claiming it.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    squares: Grid2D<Vec<usize>>,
+    patches: Vec<Patch>,
+}
+
+impl Grid {
+    /// Unclaimed fabric of `size_x` by `size_y` square inches.
+    pub fn new(size_x: usize, size_y: usize) -> Self {
+        let squares = Grid2D::new(size_x, size_y, Vec::new());
+        let patches = Vec::new();
+        Grid { squares, patches }
+    }
+
+    /// Unclaimed fabric reaching to the right and bottom edges of the patches.
+    pub fn fitting(patches: &[Patch]) -> Result<Self, PuzzleError> {
+        let size_x = patches.iter().map(Patch::end_x).max().unwrap_or(0);
+        let size_y = patches.iter().map(Patch::end_y).max().unwrap_or(0);
+        Grid::sized(size_x, size_y)
+    }
+
+    /// Unclaimed fabric of `size_x` by `size_y` square inches,
+    /// unless its squares would take more than `MAX_GRID_BYTES`.
+    pub fn sized(size_x: u128, size_y: u128) -> Result<Self, PuzzleError> {
+        let square = mem::size_of::<Vec<usize>>() as u128;
+        let bytes = size_x
+            .checked_mul(size_y)
+            .and_then(|squares| squares.checked_mul(square));
+        match bytes {
+            Some(bytes) if bytes <= MAX_GRID_BYTES => {
+                Ok(Grid::new(size_x as usize, size_y as usize))
+            }
+            _ => Err(PuzzleError::FabricTooLarge { size_x, size_y }),
+        }
+    }
+
+    // Operations on Patches
+
+    /// Claim all square inches covered by the patch.
+    ///
+    /// A patch reaching beyond the fabric is rejected, without claiming any square inch.
+    pub fn claim(&mut self, patch: Patch) -> Result<(), PuzzleError> {
+        let overrun_x = patch.end_x().saturating_sub(self.squares.width() as u128);
+        let overrun_y = patch.end_y().saturating_sub(self.squares.height() as u128);
+        if patch.is_empty() {
+            // Claims no square inch, wherever it is
+            self.patches.push(patch);
+            return Ok(());
+        }
+        if overrun_x > 0 || overrun_y > 0 {
+            return Err(PuzzleError::OutOfBounds {
+                id: patch.id,
+                overrun_x,
+                overrun_y,
+            });
+        }
+        // Both ends are on the fabric, so they fit into its coordinates
+        for y in u128::from(patch.offset_y)..patch.end_y() {
+            for x in u128::from(patch.offset_x)..patch.end_x() {
+                self.squares
+                    .get_mut(x as i64, y as i64)
+                    .expect("The patch is on the fabric")
+                    .push(patch.id);
+            }
+        }
+        self.patches.push(patch);
+        Ok(())
+    }
+
+    /// The ids of the patches claiming each square inch.
+    pub fn squares(&self) -> &Grid2D<Vec<usize>> {
+        &self.squares
+    }
+
+    /// The ids of the patches claiming the square inch, `None` if it is not on the fabric.
+    pub fn claims_at(&self, x: usize, y: usize) -> Option<&[usize]> {
+        self.squares.get(x as i64, y as i64).map(Vec::as_slice)
+    }
+
+    /// An index of the claimed patches for queries by area.
+    pub fn index(&self) -> ClaimIndex {
+        ClaimIndex::new(&self.patches)
+    }
+
+    /// Ids of all claimed patches which do not overlap any other patch.
+    pub fn solo_claimed_patches(&self) -> Vec<usize> {
+        let mut all: HashSet<_> = self.patches.iter().map(|patch| patch.id).collect();
+        for square_ids in self
+            .squares
+            .cells()
+            .iter()
+            .filter(|square| square.len() > 1)
+        {
+            for id in square_ids {
+                all.remove(id);
+            }
+        }
+        all.iter().map(|id| id.to_owned()).collect()
+    }
+
+    /// Number of square inches claimed by more than one patch.
+    pub fn count_overlapping(&self) -> usize {
+        self.squares
+            .cells()
+            .par_iter()
+            .filter(|count| count.len() > 1)
+            .count()
+    }
+}
+
+/// A claimed rectangle, `offset_x`/`offset_y` inches from the left/top edge of the fabric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Patch {
+    pub id: usize,
+    pub offset_x: u64,
+    pub offset_y: u64,
+    pub size_x: u64,
+    pub size_y: u64,
+}
+
+impl Patch {
+    /// Whether both patches claim some square inch.
+    pub fn overlaps(&self, other: &Patch) -> bool {
+        u128::from(self.offset_x) < other.end_x()
+            && u128::from(other.offset_x) < self.end_x()
+            && u128::from(self.offset_y) < other.end_y()
+            && u128::from(other.offset_y) < self.end_y()
+    }
+
+    /// The inches from the left edge to the right of the patch, which may not fit into 64 bits
+    pub fn end_x(&self) -> u128 {
+        u128::from(self.offset_x) + u128::from(self.size_x)
+    }
+
+    /// The inches from the top edge to the bottom of the patch, which may not fit into 64 bits
+    pub fn end_y(&self) -> u128 {
+        u128::from(self.offset_y) + u128::from(self.size_y)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size_x == 0 || self.size_y == 0
+    }
+}
+
+/// The patches in a tree of bounding boxes, to find the patches in an area
+/// without looking at all of them, whatever their coordinates.
+///
+/// Each node of the tree bounds the patches below it, the patches are split between its
+/// two children at the median of their centers, alternating between x and y.
+#[derive(Debug, Clone)]
+pub struct ClaimIndex {
+    patches: Vec<Patch>,
+    /// The indices of the non-empty patches, those below each node consecutive
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+    /// The index of the first patch with each id
+    by_id: HashMap<usize, usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    extent: Extent,
+    /// The range of `order` below the node
+    patches: Range<usize>,
+    /// The indices of the two children, none for a leaf
+    children: Option<(usize, usize)>,
+}
+
+/// The half open ranges of the inches covered along both axes
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Extent {
+    x: Range<u128>,
+    y: Range<u128>,
+}
+
+impl Extent {
+    fn of(patch: &Patch) -> Self {
+        Extent {
+            x: u128::from(patch.offset_x)..patch.end_x(),
+            y: u128::from(patch.offset_y)..patch.end_y(),
+        }
+    }
+
+    fn intersects(&self, other: &Extent) -> bool {
+        self.x.start < other.x.end
+            && other.x.start < self.x.end
+            && self.y.start < other.y.end
+            && other.y.start < self.y.end
+    }
+
+    /// The number of square inches in both extents
+    fn overlap(&self, other: &Extent) -> u128 {
+        let length = |a: &Range<u128>, b: &Range<u128>| {
+            a.end.min(b.end).saturating_sub(a.start.max(b.start))
+        };
+        length(&self.x, &other.x) * length(&self.y, &other.y)
+    }
+
+    fn union(&self, other: &Extent) -> Extent {
+        Extent {
+            x: self.x.start.min(other.x.start)..self.x.end.max(other.x.end),
+            y: self.y.start.min(other.y.start)..self.y.end.max(other.y.end),
+        }
+    }
+}
+
+impl ClaimIndex {
+    /// Patches per leaf of the tree
+    const LEAF_SIZE: usize = 8;
+
+    /// Index the patches, in O(n log n) for n patches.
+    pub fn new(patches: &[Patch]) -> Self {
+        let mut index = ClaimIndex {
+            patches: patches.to_vec(),
+            order: (0..patches.len())
+                .filter(|patch| !patches[*patch].is_empty())
+                .collect(),
+            nodes: Vec::new(),
+            by_id: HashMap::new(),
+        };
+        for (patch, claim) in patches.iter().enumerate() {
+            index.by_id.entry(claim.id).or_insert(patch);
+        }
+        if !index.order.is_empty() {
+            index.build(0..index.order.len(), true);
+        }
+        index
+    }
+
+    /// Add the node of the patches in the range of `order`, and the nodes below it.
+    fn build(&mut self, range: Range<usize>, split_x: bool) -> usize {
+        let extent = self.order[range.clone()]
+            .iter()
+            .map(|patch| Extent::of(&self.patches[*patch]))
+            .fold(None, |extent: Option<Extent>, next| {
+                Some(extent.map_or(next.clone(), |extent| extent.union(&next)))
+            })
+            .expect("A node has patches");
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            extent,
+            patches: range.clone(),
+            children: None,
+        });
+        if range.len() > Self::LEAF_SIZE {
+            let patches = &self.patches;
+            let center = |patch: &usize| {
+                let extent = Extent::of(&patches[*patch]);
+                if split_x {
+                    extent.x.start + extent.x.end
+                } else {
+                    extent.y.start + extent.y.end
+                }
+            };
+            let middle = range.len() / 2;
+            self.order[range.clone()].select_nth_unstable_by_key(middle, center);
+            let middle = range.start + middle;
+            let left = self.build(range.start..middle, !split_x);
+            let right = self.build(middle..range.end, !split_x);
+            self.nodes[node].children = Some((left, right));
+        }
+        node
+    }
+
+    /// The indices of the patches intersecting the extent, in no particular order
+    fn search(&self, extent: &Extent) -> Vec<usize> {
+        let mut found = Vec::new();
+        // An empty extent contains no square inch any patch could claim
+        let empty = extent.x.is_empty() || extent.y.is_empty();
+        let mut pending = if self.nodes.is_empty() || empty {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(node) = pending.pop() {
+            let node = &self.nodes[node];
+            if !node.extent.intersects(extent) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => pending.extend([left, right]),
+                None => found.extend(
+                    self.order[node.patches.clone()]
+                        .iter()
+                        .filter(|patch| Extent::of(&self.patches[**patch]).intersects(extent)),
+                ),
+            }
+        }
+        found
+    }
+
+    /// The ids of the patches claiming the square inch, in ascending order.
+    pub fn covering(&self, x: u64, y: u64) -> Vec<usize> {
+        let (x, y) = (u128::from(x), u128::from(y));
+        self.ids(&Extent {
+            x: x..x + 1,
+            y: y..y + 1,
+        })
+    }
+
+    /// The ids of the patches claiming some square inch of the area, in ascending order.
+    pub fn intersecting(&self, x: Range<u64>, y: Range<u64>) -> Vec<usize> {
+        self.ids(&Extent {
+            x: u128::from(x.start)..u128::from(x.end),
+            y: u128::from(y.start)..u128::from(y.end),
+        })
+    }
+
+    fn ids(&self, extent: &Extent) -> Vec<usize> {
+        let mut ids: Vec<usize> = self
+            .search(extent)
+            .into_iter()
+            .map(|patch| self.patches[patch].id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The ids of the other patches overlapping the patch with the id, in ascending order,
+    /// each with the number of square inches both claim. `None` if there is no such patch.
+    ///
+    /// If several patches have the id, the first one is looked at, and the others
+    /// are not reported, as no claim overlaps itself.
+    pub fn overlapping(&self, id: usize) -> Option<Vec<(usize, u128)>> {
+        let claim = *self.by_id.get(&id)?;
+        let extent = Extent::of(&self.patches[claim]);
+        let mut overlaps: Vec<(usize, u128)> = self
+            .search(&extent)
+            .into_iter()
+            .filter(|patch| self.patches[*patch].id != id)
+            .map(|patch| {
+                let other = &self.patches[patch];
+                (other.id, extent.overlap(&Extent::of(other)))
+            })
+            .collect();
+        overlaps.sort_unstable();
+        Some(overlaps)
+    }
+}
+
+/// The overlapping claims, found without a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sweep {
+    /// Number of square inches claimed by more than one patch
+    pub overlapping: u64,
+    /// Ids of the patches which do not overlap any other patch, in ascending order
+    pub solo: Vec<usize>,
+}
+
+/// Find the overlapping claims by sweeping a line from the left to the right edge of the fabric,
+/// stopping at the left and right edges of the patches.
+///
+/// Along the line the top and bottom edges of the patches divide it into segments,
+/// which are kept in two segment trees: how many patches cover each segment, and when
+/// a patch last started there. A patch overlaps another if it starts where another one
+/// already lies, or if another one starts within it before it ends. Both the time and
+/// the memory are O(n log n) for n patches, whatever their coordinates.
+pub fn sweep(patches: &[Patch]) -> Result<Sweep, PuzzleError> {
+    let mut ys: Vec<u128> = patches
+        .iter()
+        .flat_map(|patch| vec![u128::from(patch.offset_y), patch.end_y()])
+        .collect();
+    ys.sort_unstable();
+    ys.dedup();
+    let segments = |patch: &Patch| {
+        let index = |y| ys.binary_search(&y).expect("Every edge is a coordinate");
+        index(u128::from(patch.offset_y))..index(patch.end_y())
+    };
+
+    // Empty patches claim nothing, so they never overlap
+    let claims: Vec<&Patch> = patches.iter().filter(|patch| !patch.is_empty()).collect();
+    let mut starts: Vec<usize> = (0..claims.len()).collect();
+    starts.sort_by_key(|claim| claims[*claim].offset_x);
+    let mut ends = starts.clone();
+    ends.sort_by_key(|claim| claims[*claim].end_x());
+    let mut xs: Vec<u128> = claims
+        .iter()
+        .flat_map(|patch| vec![u128::from(patch.offset_x), patch.end_x()])
+        .collect();
+    xs.sort_unstable();
+    xs.dedup();
+
+    let mut coverage = Coverage::new(&ys);
+    let mut started = LastStarted::new(ys.len().saturating_sub(1));
+    // The time each claim started, counting the claims started before
+    let mut times = vec![0; claims.len()];
+    let mut overlapping = vec![false; claims.len()];
+    let (mut next_start, mut next_end) = (0, 0);
+    let mut area: u128 = 0;
+    let mut previous_x = 0;
+    for x in xs {
+        area = coverage
+            .covered_twice()
+            .checked_mul(x - previous_x)
+            .and_then(|slice| area.checked_add(slice))
+            .ok_or(PuzzleError::AreaOverflow)?;
+        previous_x = x;
+
+        while next_end < ends.len() && claims[ends[next_end]].end_x() == x {
+            let claim = ends[next_end];
+            let segments = segments(claims[claim]);
+            overlapping[claim] |= started.latest(segments.clone()) > times[claim];
+            coverage.add(segments, -1);
+            next_end += 1;
+        }
+        let first_start = next_start;
+        while next_start < starts.len() && u128::from(claims[starts[next_start]].offset_x) == x {
+            let claim = starts[next_start];
+            times[claim] = next_start + 1;
+            coverage.add(segments(claims[claim]), 1);
+            started.start(segments(claims[claim]), times[claim]);
+            next_start += 1;
+        }
+        for claim in &starts[first_start..next_start] {
+            overlapping[*claim] |= coverage.most(segments(claims[*claim])) > 1;
+        }
+    }
+
+    let overlapping_ids: HashSet<usize> = (0..claims.len())
+        .filter(|claim| overlapping[*claim])
+        .map(|claim| claims[claim].id)
+        .collect();
+    let mut solo: Vec<usize> = patches
+        .iter()
+        .map(|patch| patch.id)
+        .filter(|id| !overlapping_ids.contains(id))
+        .collect();
+    solo.sort_unstable();
+    Ok(Sweep {
+        overlapping: u64::try_from(area).map_err(|_| PuzzleError::AreaOverflow)?,
+        solo,
+    })
+}
+
+/// How many patches cover the segments between consecutive coordinates.
+///
+/// Each node counts the patches covering all of its segments but not all of its parent's,
+/// the counts are never pushed down to the children.
+struct Coverage {
+    segments: usize,
+    /// The length of the segments of the node
+    lengths: Vec<u128>,
+    counts: Vec<i64>,
+    /// Length covered at least once and at least twice, counting the node and its children
+    once: Vec<u128>,
+    twice: Vec<u128>,
+    /// The largest number of patches covering a segment, counting the node and its children
+    most: Vec<i64>,
+}
+
+impl Coverage {
+    fn new(ys: &[u128]) -> Self {
+        let segments = ys.len().saturating_sub(1);
+        let nodes = 4 * segments.max(1);
+        let mut coverage = Coverage {
+            segments,
+            lengths: vec![0; nodes],
+            counts: vec![0; nodes],
+            once: vec![0; nodes],
+            twice: vec![0; nodes],
+            most: vec![0; nodes],
+        };
+        if segments > 0 {
+            coverage.measure(1, 0..segments, ys);
+        }
+        coverage
+    }
+
+    fn measure(&mut self, node: usize, range: Range<usize>, ys: &[u128]) {
+        self.lengths[node] = ys[range.end] - ys[range.start];
+        if range.end - range.start > 1 {
+            let middle = range.start + (range.end - range.start) / 2;
+            self.measure(2 * node, range.start..middle, ys);
+            self.measure(2 * node + 1, middle..range.end, ys);
+        }
+    }
+
+    /// The length of the line covered by at least two patches
+    fn covered_twice(&self) -> u128 {
+        self.twice[1]
+    }
+
+    fn add(&mut self, segments: Range<usize>, delta: i64) {
+        self.update(1, 0..self.segments, &segments, delta);
+    }
+
+    fn update(&mut self, node: usize, range: Range<usize>, segments: &Range<usize>, delta: i64) {
+        if segments.end <= range.start || range.end <= segments.start {
+            return;
+        }
+        if segments.start <= range.start && range.end <= segments.end {
+            self.counts[node] += delta;
+        } else {
+            let middle = range.start + (range.end - range.start) / 2;
+            self.update(2 * node, range.start..middle, segments, delta);
+            self.update(2 * node + 1, middle..range.end, segments, delta);
+        }
+        self.pull(node, range);
+    }
+
+    fn pull(&mut self, node: usize, range: Range<usize>) {
+        let leaf = range.end - range.start == 1;
+        let (children_once, children_twice, children_most) = if leaf {
+            (0, 0, 0)
+        } else {
+            let (left, right) = (2 * node, 2 * node + 1);
+            (
+                self.once[left] + self.once[right],
+                self.twice[left] + self.twice[right],
+                self.most[left].max(self.most[right]),
+            )
+        };
+        let length = self.lengths[node];
+        self.once[node] = if self.counts[node] > 0 {
+            length
+        } else {
+            children_once
+        };
+        self.twice[node] = match self.counts[node] {
+            count if count >= 2 => length,
+            1 => children_once,
+            _ => children_twice,
+        };
+        self.most[node] = self.counts[node] + children_most;
+    }
+
+    /// The largest number of patches covering any of the segments
+    fn most(&self, segments: Range<usize>) -> i64 {
+        self.query(1, 0..self.segments, &segments)
+    }
+
+    fn query(&self, node: usize, range: Range<usize>, segments: &Range<usize>) -> i64 {
+        if segments.end <= range.start || range.end <= segments.start {
+            return 0;
+        }
+        if segments.start <= range.start && range.end <= segments.end {
+            return self.most[node];
+        }
+        let middle = range.start + (range.end - range.start) / 2;
+        self.counts[node]
+            + self
+                .query(2 * node, range.start..middle, segments)
+                .max(self.query(2 * node + 1, middle..range.end, segments))
+    }
+}
+
+/// The latest time a patch started on the segments between consecutive coordinates.
+///
+/// Times only grow, so each node keeps the latest time of the patches covering all
+/// of its segments, without pushing it down to the children.
+struct LastStarted {
+    segments: usize,
+    times: Vec<usize>,
+    /// The latest time, counting the node and its children
+    latest: Vec<usize>,
+}
+
+impl LastStarted {
+    fn new(segments: usize) -> Self {
+        let nodes = 4 * segments.max(1);
+        LastStarted {
+            segments,
+            times: vec![0; nodes],
+            latest: vec![0; nodes],
+        }
+    }
+
+    fn start(&mut self, segments: Range<usize>, time: usize) {
+        self.update(1, 0..self.segments, &segments, time);
+    }
+
+    fn update(&mut self, node: usize, range: Range<usize>, segments: &Range<usize>, time: usize) {
+        if segments.end <= range.start || range.end <= segments.start {
+            return;
+        }
+        if segments.start <= range.start && range.end <= segments.end {
+            self.times[node] = self.times[node].max(time);
+        } else {
+            let middle = range.start + (range.end - range.start) / 2;
+            self.update(2 * node, range.start..middle, segments, time);
+            self.update(2 * node + 1, middle..range.end, segments, time);
+        }
+        self.latest[node] = self.latest[node].max(time);
+    }
+
+    /// The latest time a patch started on any of the segments
+    fn latest(&self, segments: Range<usize>) -> usize {
+        self.query(1, 0..self.segments, &segments)
+    }
+
+    fn query(&self, node: usize, range: Range<usize>, segments: &Range<usize>) -> usize {
+        if segments.end <= range.start || range.end <= segments.start {
+            return 0;
+        }
+        if segments.start <= range.start && range.end <= segments.end {
+            return self.latest[node];
+        }
+        let middle = range.start + (range.end - range.start) / 2;
+        self.times[node].max(
+            self.query(2 * node, range.start..middle, segments)
+                .max(self.query(2 * node + 1, middle..range.end, segments)),
+        )
+    }
+}
+
+// Format:
+// #123 @ 3,2: 5x4
+lazy_static! {
+    static ref FROM_STR_RX: Regex = Regex::new(
+        r##"^#(?P<id>[0-9]+)\s+@\s+(?P<offset_x>[0-9]+),(?P<offset_y>[0-9]+):\s+(?P<size_x>[0-9]+)x(?P<size_y>[0-9]+)$"##
+    ).unwrap();
+}
+impl FromStr for Patch {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let caps = FROM_STR_RX.captures(s).ok_or_else(|| PuzzleError::Parse {
+            span: Span::locate(s, s),
+            expected: "a claim like '#123 @ 3,2: 5x4'",
+        })?;
+        // all groups are mandatory, but the numbers may be too large
+        let number = |name| {
+            let text = caps.name(name).expect("Claim matched").as_str();
+            text.parse::<u64>().map_err(|_| PuzzleError::Parse {
+                span: Span::locate(s, text),
+                expected: "a smaller number",
+            })
+        };
+
+        let id = number("id")? as usize;
+        let offset_x = number("offset_x")?;
+        let offset_y = number("offset_y")?;
+        let size_x = number("size_x")?;
+        let size_y = number("size_y")?;
+        Ok(Patch {
+            id,
+            offset_x,
+            offset_y,
+            size_x,
+            size_y,
+        })
+    }
+}
+
+/// The claim as in the puzzle input, e.g. `#123 @ 3,2: 5x4`
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} @ {},{}: {}x{}",
+            self.id, self.offset_x, self.offset_y, self.size_x, self.size_y
+        )
+    }
+}
+
+#[test]
+fn generate_single_solo_claim() {
+    for size in 1..10 {
+        let input = Day03::generate(&mut Rng::new(size as u64), size);
+        let patches = Day03::parse(&input, &Fabric::default()).unwrap();
+
+        assert_eq!(size.max(3), patches.len());
+        assert!(Day03::part2(&patches, &Fabric::default()).is_ok());
+    }
+}
+
+#[test]
+fn parse_patch() {
+    let input = "#123 @ 3,2: 5x4";
+
+    let patch: Patch = FromStr::from_str(input).unwrap();
+
+    let expected = Patch {
+        id: 123,
+        offset_x: 3,
+        offset_y: 2,
+        size_x: 5,
+        size_y: 4,
+    };
+    assert_eq!(patch, expected)
+}
+
+#[test]
+fn patch_round_trip() {
+    aoc_common::check::round_trip(|choices| Patch {
+        id: choices.below(usize::MAX),
+        offset_x: choices.below(2000) as u64,
+        offset_y: choices.below(2000) as u64,
+        size_x: choices.below(100) as u64,
+        size_y: choices.below(100) as u64,
+    });
+}
+
+#[test]
+fn parse_patch_error() {
+    let input = "#1 @ 1,3: 4x4\n#2 @ 3,1 4x4\n#3 @ 5,99999999999999999999: 2x2";
+
+    let errors: Vec<_> = parse_lines(input, Patch::from_str)
+        .filter_map(Result::err)
+        .collect();
+
+    assert_eq!(
+        vec![
+            PuzzleError::Parse {
+                span: Span::new(2, 1, "#2 @ 3,1 4x4"),
+                expected: "a claim like '#123 @ 3,2: 5x4'",
+            },
+            PuzzleError::Parse {
+                span: Span::new(3, 8, "99999999999999999999"),
+                expected: "a smaller number",
+            }
+        ],
+        errors
+    );
+}
+
+#[test]
+fn claim_origin() {
+    let mut grid = Grid::new(2, 2);
+    let patch = Patch {
+        id: 1,
+        offset_y: 0,
+        offset_x: 0,
+        size_y: 1,
+        size_x: 1,
+    };
+
+    grid.claim(patch).unwrap();
+
+    assert_eq!(grid.squares.cells()[0].len(), 1);
+    assert_eq!(grid.squares.cells()[1].len(), 0);
+    assert_eq!(grid.squares.cells()[2].len(), 0);
+    assert_eq!(grid.squares.cells()[3].len(), 0);
+}
+
+#[test]
+fn claim_last() {
+    let mut grid = Grid::new(2, 2);
+    let patch = Patch {
+        id: 1,
+        offset_y: 1,
+        offset_x: 1,
+        size_y: 1,
+        size_x: 1,
+    };
+
+    grid.claim(patch).unwrap();
+
+    assert_eq!(grid.squares.cells()[0].len(), 0);
+    assert_eq!(grid.squares.cells()[1].len(), 0);
+    assert_eq!(grid.squares.cells()[2].len(), 0);
+    assert_eq!(grid.squares.cells()[3].len(), 1);
+}
+
+#[test]
+fn claim_all() {
+    let mut grid = Grid::new(2, 2);
+    let patch = Patch {
+        id: 1,
+        offset_y: 0,
+        offset_x: 0,
+        size_y: 2,
+        size_x: 2,
+    };
+
+    grid.claim(patch).unwrap();
+
+    assert_eq!(grid.squares.cells()[0].len(), 1);
+    assert_eq!(grid.squares.cells()[1].len(), 1);
+    assert_eq!(grid.squares.cells()[2].len(), 1);
+    assert_eq!(grid.squares.cells()[3].len(), 1);
+}
+
+#[test]
+fn sweep_agrees_with_grid() {
+    aoc_common::check::check(
+        |choices| {
+            let patches = choices.vec(0..=8, |choices| Patch {
+                id: 0,
+                offset_x: choices.below(8) as u64,
+                offset_y: choices.below(8) as u64,
+                size_x: choices.below(5) as u64,
+                size_y: choices.below(5) as u64,
+            });
+            (1..)
+                .zip(patches)
+                .map(|(id, patch)| Patch { id, ..patch })
+                .collect::<Vec<_>>()
+        },
+        |patches| {
+            let fabric = Fabric {
+                size_x: 12,
+                size_y: 12,
+                ..Fabric::default()
+            };
+            let grid = claim_fabric(patches, &fabric).unwrap();
+            let mut solo = grid.solo_claimed_patches();
+            solo.sort_unstable();
+            let expected = Sweep {
+                overlapping: grid.count_overlapping() as u64,
+                solo,
+            };
+            match sweep(patches) {
+                Ok(ref found) if *found == expected => Ok(()),
+                found => Err(format!("{:?} instead of {:?}", found, expected)),
+            }
+        },
+    );
+}
+
+#[test]
+fn sweep_huge_fabric() {
+    let far = u64::MAX - 10;
+    let patches: Vec<Patch> = [
+        "#1 @ 0,0: 4294967296x4294967296",
+        "#2 @ 4294967295,4294967295: 2x3",
+        "#3 @ 18446744073709551605,18446744073709551605: 20x20",
+        "#4 @ 18446744073709551615,18446744073709551615: 5x5",
+        "#5 @ 9000000000000000000,7: 3x3",
+    ]
+    .iter()
+    .map(|claim| claim.parse().unwrap())
+    .collect();
+    assert_eq!(far, patches[2].offset_x);
+
+    let sweep = sweep(&patches).unwrap();
+
+    assert_eq!(1 + 25, sweep.overlapping);
+    assert_eq!(vec![5], sweep.solo);
+    let fabric = Fabric {
+        engine: Engine::Sweep,
+        ..Fabric::default()
+    };
+    assert_eq!(Ok(5), Day03::part2(&patches, &fabric));
+    assert!(claim_fabric(&patches, &Fabric::default()).is_err());
+}
+
+#[test]
+fn claim_out_of_bounds() {
+    let mut grid = Grid::new(3, 2);
+    // The overrun on the right must not wrap into the next row
+    let right: Patch = "#7 @ 2,0: 2x1".parse().unwrap();
+    let both: Patch = "#8 @ 1,1: 3x4".parse().unwrap();
+
+    assert_eq!(
+        Err(PuzzleError::OutOfBounds {
+            id: 7,
+            overrun_x: 1,
+            overrun_y: 0,
+        }),
+        grid.claim(right)
+    );
+    let error = grid.claim(both).unwrap_err();
+    assert_eq!(
+        "Claim #8 reaches beyond the fabric by 1 inches to the right and 3 at the bottom",
+        error.to_string()
+    );
+    assert!(grid.squares.cells().iter().all(Vec::is_empty));
+    assert!(grid.patches.is_empty());
+}
+
+#[test]
+fn claim_auto_bounds() {
+    let patches: Vec<Patch> = ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"]
+        .iter()
+        .map(|claim| claim.parse().unwrap())
+        .collect();
+    let fabric = Fabric {
+        size_x: 2,
+        size_y: 2,
+        bounds: Bounds::Auto,
+        ..Fabric::default()
+    };
+
+    let grid = claim_fabric(&patches, &fabric).unwrap();
+
+    assert_eq!((7, 7), (grid.squares.width(), grid.squares.height()));
+    assert_eq!(4, grid.count_overlapping());
+    let huge: Patch = "#4 @ 18446744073709551615,0: 18446744073709551615x1"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        Err(PuzzleError::FabricTooLarge {
+            size_x: 2 * u128::from(u64::MAX),
+            size_y: 1,
+        }),
+        Grid::fitting(&[huge]).map(|_| ())
+    );
+}
+
+#[test]
+fn index_example() {
+    let patches: Vec<Patch> = ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"]
+        .iter()
+        .map(|claim| claim.parse().unwrap())
+        .collect();
+    let grid = claim_fabric(&patches, &Fabric::default()).unwrap();
+
+    let index = grid.index();
+
+    assert_eq!(Some(&[1, 2][..]), grid.claims_at(3, 3));
+    assert_eq!(vec![1, 2], index.covering(3, 3));
+    assert_eq!(vec![3], index.covering(6, 6));
+    assert_eq!(Some(vec![(2, 4)]), index.overlapping(1));
+    assert_eq!(Some(vec![]), index.overlapping(3));
+    assert_eq!(None, index.overlapping(4));
+    assert_eq!(vec![2, 3], index.intersecting(5..9, 0..9));
+    assert!(index.intersecting(0..1, 0..9).is_empty());
+    assert!(index.covering(u64::MAX, u64::MAX).is_empty());
+}
+
+#[test]
+fn index_duplicate_ids() {
+    let patches: Vec<Patch> = ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#1 @ 2,2: 2x2"]
+        .iter()
+        .map(|claim| claim.parse().unwrap())
+        .collect();
+
+    let index = ClaimIndex::new(&patches);
+
+    assert_eq!(Some(vec![(2, 4)]), index.overlapping(1));
+    // Both patches with the id 1 overlap the patch with the id 2
+    assert_eq!(Some(vec![(1, 2), (1, 4)]), index.overlapping(2));
+    assert_eq!(vec![1, 1, 2], index.covering(3, 3));
+}
+
+#[test]
+fn index_agrees_with_all_patches() {
+    aoc_common::check::check(
+        |choices| {
+            let patches = choices.vec(0..=40, |choices| Patch {
+                id: 0,
+                offset_x: choices.below(20) as u64,
+                offset_y: choices.below(20) as u64,
+                size_x: choices.below(6) as u64,
+                size_y: choices.below(6) as u64,
+            });
+            let patches: Vec<Patch> = (1..)
+                .zip(patches)
+                .map(|(id, patch)| Patch { id, ..patch })
+                .collect();
+            let x = choices.below(20) as u64;
+            let y = choices.below(20) as u64;
+            (
+                patches,
+                x..x + choices.below(6) as u64,
+                y..y + choices.below(6) as u64,
+            )
+        },
+        |(patches, x, y)| {
+            let index = ClaimIndex::new(patches);
+            let area = Patch {
+                id: 0,
+                offset_x: x.start,
+                offset_y: y.start,
+                size_x: x.end - x.start,
+                size_y: y.end - y.start,
+            };
+            let claimed = |patch: &&Patch| !patch.is_empty() && !area.is_empty();
+            let expected: Vec<usize> = patches
+                .iter()
+                .filter(claimed)
+                .filter(|patch| patch.overlaps(&area))
+                .map(|patch| patch.id)
+                .collect();
+            let found = index.intersecting(x.clone(), y.clone());
+            if found != expected {
+                return Err(format!("{:?} intersect, not {:?}", expected, found));
+            }
+            for patch in patches {
+                let expected: Vec<(usize, u128)> = patches
+                    .iter()
+                    .filter(|other| other.id != patch.id && !other.is_empty() && !patch.is_empty())
+                    .filter(|other| other.overlaps(patch))
+                    .map(|other| (other.id, Extent::of(patch).overlap(&Extent::of(other))))
+                    .collect();
+                let found = index.overlapping(patch.id);
+                if found.as_ref() != Some(&expected) {
+                    return Err(format!(
+                        "{:?} overlap #{}, not {:?}",
+                        expected, patch.id, found
+                    ));
+                }
+            }
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn claim_empty_patches() {
+    let mut grid = Grid::new(2, 2);
+    let patches: Vec<Patch> = [
+        "#1 @ 0,18446744073709551615: 0x5",
+        "#2 @ 0,0: 0x18446744073709551615",
+    ]
+    .iter()
+    .map(|claim| claim.parse().unwrap())
+    .collect();
+
+    for patch in &patches {
+        grid.claim(patch.clone()).unwrap();
+    }
+
+    assert!(grid.squares.cells().iter().all(Vec::is_empty));
+    let mut solo = grid.solo_claimed_patches();
+    solo.sort_unstable();
+    assert_eq!(vec![1, 2], solo);
+}
+
+#[test]
+fn grid_too_large() {
+    let patches: Vec<Patch> = [
+        "#1 @ 0,0: 1152921504606846976x1",
+        "#2 @ 0,0: 100000000x100000",
+    ]
+    .iter()
+    .map(|claim| claim.parse().unwrap())
+    .collect();
+    let auto = Fabric {
+        bounds: Bounds::Auto,
+        ..Fabric::default()
+    };
+    let huge = Fabric {
+        size_x: 100_000_000,
+        size_y: 100_000,
+        ..Fabric::default()
+    };
+
+    assert_eq!(
+        Err(PuzzleError::FabricTooLarge {
+            size_x: 1 << 60,
+            size_y: 1,
+        }),
+        Day03::part1(&patches[..1].to_vec(), &auto)
+    );
+    assert_eq!(
+        Err(PuzzleError::FabricTooLarge {
+            size_x: 100_000_000,
+            size_y: 100_000,
+        }),
+        Day03::part1(&patches[1..].to_vec(), &auto)
+    );
+    assert!(Day03::part1(&patches[1..].to_vec(), &huge).is_err());
+}
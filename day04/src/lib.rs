@@ -0,0 +1,639 @@
+//! Day 4: Repose Record
+//!
+//! Replays the log of the guards' shifts to find out which guard sleeps
+//! the most, and on which minute.
+
+extern crate aoc_common;
+#[macro_use]
+extern crate lazy_static;
+extern crate regex;
+
+use aoc_common::diagnostic::{parse_lines, Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::input::{read_lines, StreamError};
+use aoc_common::random::Rng;
+use aoc_common::{Answer, Json, Solution};
+use regex::Regex;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// Minute after midnight
+pub type Minute = usize;
+pub type GuardId = usize;
+
+/// Solution of day 4 for the runners
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day4.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = World;
+    type Params = ();
+    type Answer1 = Strategy;
+    type Answer2 = Strategy;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        // The line index is kept to point to the original line on errors
+        let entries: Result<Vec<_>, _> = parse_lines(input, FromStr::from_str)
+            .enumerate()
+            .map(|(index, entry)| entry.map(|entry| (index, entry)))
+            .collect();
+        World::from_entries(entries?)
+    }
+
+    /// All entries are parsed before replaying them, as the log must be sorted first.
+    fn read<R: BufRead>(
+        reader: R,
+        _params: &Self::Params,
+    ) -> Result<Self::Input, StreamError<Self::Error>> {
+        let entries: Result<Vec<_>, _> = read_entries(reader)
+            .enumerate()
+            .map(|(index, entry)| entry.map(|entry| (index, entry)))
+            .collect();
+        Ok(World::from_entries(entries?)?)
+    }
+
+    fn part1(world: &Self::Input, _params: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        let (sleepiest_guard_id, _amount_slept) =
+            world.get_sleepiest_guard().ok_or(PuzzleError::NoSleep)?;
+
+        let (sleepiest_minute, count) = world
+            .guards
+            .get(&sleepiest_guard_id)
+            .expect("Sleepiest guard must be in guards")
+            .sleepiest_minute()
+            .expect("Guards always have 60 minutes");
+
+        Ok(Strategy {
+            guard: sleepiest_guard_id,
+            minute: sleepiest_minute,
+            times_asleep: count,
+        })
+    }
+
+    fn part2(world: &Self::Input, _params: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let (most_sleepy_guard, most_sleepy_minute, most_sleepy_count) = world
+            .get_most_slept_minute_by_single_guard()
+            .ok_or(PuzzleError::NoSleep)?;
+
+        Ok(Strategy {
+            guard: most_sleepy_guard,
+            minute: most_sleepy_minute,
+            times_asleep: most_sleepy_count,
+        })
+    }
+}
+
+impl Generate for Day04 {
+    const SIZE: usize = 300;
+
+    /// The shuffled log of `size` shifts, one per night, by about one guard per 15 shifts.
+    ///
+    /// Each shift begins shortly before or after midnight, and the guard takes up to 3 naps
+    /// after 00:03, at least one in the first shift.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut guards = Vec::new();
+        while guards.len() < (size / 15).max(1) {
+            let guard = 10 + rng.below(4000);
+            if !guards.contains(&guard) {
+                guards.push(guard);
+            }
+        }
+
+        let mut log = Vec::new();
+        for night in 0..size {
+            let begin = if rng.chance(1, 2) {
+                format!("{} 23:{:02}", date(night), 45 + rng.below(15))
+            } else {
+                format!("{} 00:{:02}", date(night + 1), rng.below(4))
+            };
+            log.push(format!(
+                "[{}] Guard #{} begins shift",
+                begin,
+                rng.choose(&guards)
+            ));
+
+            let naps = rng.range(if night == 0 { 1 } else { 0 }..=3) as usize;
+            let mut minutes: Vec<Minute> = (4..60).collect();
+            rng.shuffle(&mut minutes);
+            minutes.truncate(2 * naps);
+            minutes.sort_unstable();
+            for nap in minutes.chunks(2) {
+                let day = date(night + 1);
+                log.push(format!("[{} 00:{:02}] falls asleep", day, nap[0]));
+                log.push(format!("[{} 00:{:02}] wakes up", day, nap[1]));
+            }
+        }
+        rng.shuffle(&mut log);
+
+        log.iter().map(|entry| format!("{}\n", entry)).collect()
+    }
+}
+
+// The date `days` after the 1st of January 1518, ignoring leap years
+fn date(days: usize) -> String {
+    const MONTHS: [usize; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let (year, mut day) = (1518 + days / 365, days % 365);
+    let mut month = 0;
+    while day >= MONTHS[month] {
+        day -= MONTHS[month];
+        month += 1;
+    }
+    format!("{}-{:02}-{:02}", year, month + 1, day + 1)
+}
+
+/// The guard to sneak past on the minute they are asleep most often.
+/// The answer is the product of the guard id and the minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strategy {
+    pub guard: GuardId,
+    pub minute: Minute,
+    /// How often the guard slept on that minute
+    pub times_asleep: usize,
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.guard * self.minute)
+    }
+}
+
+impl Answer for Strategy {
+    fn value(&self) -> Json {
+        Json::from(self.guard * self.minute)
+    }
+
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("guard", Json::from(self.guard)),
+            ("minute", Json::from(self.minute)),
+            ("times_asleep", Json::from(self.times_asleep)),
+        ]
+    }
+}
+
+/// Everything that can go wrong on day 4
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A line is not a log entry
+    Parse { span: Span, expected: &'static str },
+    /// A log entry is not possible at its time
+    Shift { span: Span, error: ShiftError },
+    /// No guard ever fell asleep
+    NoSleep,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span, expected } => {
+                write!(f, "{}: expected {}, found '{}'", span, expected, span.text)
+            }
+            PuzzleError::Shift { span, error } => write!(f, "{}: {}", span, error),
+            PuzzleError::NoSleep => write!(f, "No guard slept at all"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span, .. } | PuzzleError::Shift { span, .. } => Some(span),
+            PuzzleError::NoSleep => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span, .. } | PuzzleError::Shift { span, .. } => Some(span),
+            PuzzleError::NoSleep => None,
+        }
+    }
+}
+
+/// An event which is not possible in the current state of the guard post
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShiftError {
+    AlreadyAsleep,
+    AlreadyAwake,
+    NoGuard,
+}
+
+impl fmt::Display for ShiftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShiftError::AlreadyAsleep => write!(f, "Can't sleep in: Guard is asleep."),
+            ShiftError::AlreadyAwake => write!(f, "Can't wake up: Guard is already awake."),
+            ShiftError::NoGuard => write!(f, "No guard set, can't set sleep duration"),
+        }
+    }
+}
+
+impl Error for ShiftError {}
+
+/// The state of the guard post, recording how long each guard slept.
+#[derive(Debug, Clone, Default)]
+pub struct World {
+    guards: HashMap<GuardId, Guard>,
+    current_guard: Option<GuardId>,
+    asleep_since: Option<Minute>,
+}
+
+impl World {
+    /// No guard on duty, and no sleep recorded yet.
+    pub fn new() -> Self {
+        World {
+            guards: HashMap::new(),
+            current_guard: None,
+            asleep_since: None,
+        }
+    }
+
+    /// Replay the entries of the log in the order of their time,
+    /// each given with the 0-based index of its line to point to it on errors.
+    pub fn from_entries(mut entries: Vec<(usize, Entry)>) -> Result<Self, PuzzleError> {
+        // Sort input before processing as needed guarantee
+        // for ignoring Guard Changes at 23:xx
+        entries.sort_by(|(_, a), (_, b)| a.timestamp.cmp(&b.timestamp));
+
+        let mut world = World::new();
+        for (index, entry) in entries {
+            world
+                .step_event(&entry.event)
+                .map_err(|error| PuzzleError::Shift {
+                    span: Span::new(index + 1, 1, entry.timestamp),
+                    error,
+                })?;
+        }
+        Ok(world)
+    }
+
+    /// Apply a single event, failing if it is not possible in the current state.
+    pub fn step_event(&mut self, event: &Event) -> Result<(), ShiftError> {
+        match event {
+            Event::SwitchOver(id) => {
+                //                println!("New Guard: {}", id);
+                self.current_guard = Some(*id);
+            }
+            Event::SleepIn(minute) => {
+                //                println!("Sleep in at: {}", minute);
+                if self.asleep_since.is_some() {
+                    return Err(ShiftError::AlreadyAsleep);
+                }
+                self.asleep_since = Some(*minute);
+            }
+            Event::WakeUp(to_minute) => {
+                //                println!("Wake up at: {}", to_minute);
+                if let Some(from_minute) = self.asleep_since {
+                    if let Some(guard) = self.current_guard {
+                        for min in from_minute..*to_minute {
+                            self.guards
+                                .entry(guard)
+                                .and_modify(|count| count.asleep_per_minute[min] += 1)
+                                .or_insert_with(|| {
+                                    let mut guard = Guard::new();
+                                    guard.asleep_per_minute[min] += 1;
+                                    guard
+                                });
+                        }
+                    } else {
+                        return Err(ShiftError::NoGuard);
+                    }
+                } else {
+                    return Err(ShiftError::AlreadyAwake);
+                }
+
+                self.asleep_since = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply all events, which must be sorted by time.
+    pub fn process_sorted_events(&mut self, events: &[Event]) -> Result<(), ShiftError> {
+        for event in events {
+            self.step_event(event)?;
+        }
+        Ok(())
+    }
+
+    /// All guards which slept at least once, by id.
+    pub fn guards(&self) -> &HashMap<GuardId, Guard> {
+        &self.guards
+    }
+
+    /// The guard sleeping the most minutes, and the number of minutes slept.
+    pub fn get_sleepiest_guard(&self) -> Option<(GuardId, usize)> {
+        self.guards
+            .iter()
+            .map(|(id, guard)| (id.to_owned(), guard.asleep_per_minute.iter().sum()))
+            .max_by_key(|x: &(usize, usize)| x.1)
+    }
+
+    // FIXME improve naming
+    /// The guard, minute and count of the minute some guard slept on more often
+    /// than any guard on any other minute.
+    pub fn get_most_slept_minute_by_single_guard(&self) -> Option<(GuardId, Minute, usize)> {
+        self.guards
+            .iter()
+            .filter_map(|(id, guard)| {
+                if let Some((minute, max_asleep)) = guard
+                    .asleep_per_minute
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .max_by_key(|x| x.1)
+                {
+                    Some((*id, minute, max_asleep))
+                } else {
+                    None
+                }
+            })
+            .max_by_key(|x| x.2)
+    }
+}
+
+/// The sleep record of a single guard.
+#[derive(Debug, Clone)]
+pub struct Guard {
+    asleep_per_minute: Vec<usize>,
+}
+
+impl Guard {
+    fn new() -> Self {
+        Guard {
+            asleep_per_minute: vec![0; 60],
+        }
+    }
+
+    /// How often the guard slept during each minute of the midnight hour.
+    pub fn asleep_per_minute(&self) -> &[usize] {
+        &self.asleep_per_minute
+    }
+
+    /// The minute the guard slept on most often, and how often.
+    pub fn sleepiest_minute(&self) -> Option<(Minute, usize)> {
+        self.asleep_per_minute
+            .iter()
+            .cloned()
+            .enumerate()
+            .max_by_key(|x| x.1)
+    }
+}
+
+/// The action of a line of the guards' log, parsed with its `Entry`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    // Minute needed?
+    // Not if we are guaranteed sorted input an sleep times between 00:00 and 00:59
+    SwitchOver(GuardId),
+    SleepIn(Minute),
+    WakeUp(Minute),
+}
+
+/// A single line of the guards' log, with the time of the event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// The time as written in the log, e.g. `[1518-11-01 00:05]`, which sorts by time
+    pub timestamp: String,
+    pub event: Event,
+}
+
+/// The entries of the log, parsed one line at a time while reading them.
+pub fn read_entries<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<Entry, StreamError<PuzzleError>>> {
+    read_lines(reader, FromStr::from_str)
+}
+
+lazy_static! {
+    static ref FROM_STR_RX: Regex =
+        Regex::new(r##"^(\[[^:]+:([0-9]{2})\])\s+(Guard #([0-9]+) begins shift$|.*)"##).unwrap();
+}
+
+impl FromStr for Entry {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let parse_error = |text, expected| PuzzleError::Parse {
+            span: Span::locate(s, text),
+            expected,
+        };
+        let caps = FROM_STR_RX
+            .captures(s)
+            .ok_or_else(|| parse_error(s, "a log entry like '[1518-11-01 00:05] falls asleep'"))?;
+
+        // The minute has always two digits, but not every two digits are a minute
+        let minute = caps.get(2).expect("Minute matched").as_str();
+        let minute: Minute = minute
+            .parse()
+            .ok()
+            .filter(|minute| *minute < 60)
+            .ok_or_else(|| parse_error(minute, "a minute below 60"))?;
+
+        let event = if let Some(guard_id) = caps.get(4) {
+            let guard_id = guard_id.as_str();
+            let guard_id = guard_id
+                .parse()
+                .map_err(|_| parse_error(guard_id, "a smaller guard id"))?;
+            Event::SwitchOver(guard_id)
+        } else {
+            // The action is mandatory in the pattern
+            let event = caps.get(3).expect("Action matched").as_str();
+
+            match event {
+                "falls asleep" => Event::SleepIn(minute),
+                "wakes up" => Event::WakeUp(minute),
+                _ => {
+                    return Err(parse_error(
+                        event,
+                        "'Guard #ID begins shift', 'falls asleep' or 'wakes up'",
+                    ))
+                }
+            }
+        };
+        Ok(Entry {
+            timestamp: caps[1].to_owned(),
+            event,
+        })
+    }
+}
+
+/// The action as in the log, without the time, e.g. `falls asleep`
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::SwitchOver(guard_id) => write!(f, "Guard #{} begins shift", guard_id),
+            Event::SleepIn(_) => write!(f, "falls asleep"),
+            Event::WakeUp(_) => write!(f, "wakes up"),
+        }
+    }
+}
+
+/// The line of the log, e.g. `[1518-11-01 00:05] falls asleep`
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.timestamp, self.event)
+    }
+}
+
+// Format:
+// [1518-11-01 00:00] Guard #10 begins shift
+// [1518-11-01 00:05] falls asleep
+// [1518-11-01 00:25] wakes up
+
+#[test]
+fn parse_event() {
+    let guard = "[1518-11-01 00:00] Guard #10 begins shift";
+    let asleep = "[1518-11-01 00:05] falls asleep";
+    let wakeup = "[1518-11-01 00:25] wakes up";
+
+    let guard_event = Entry::from_str(guard).unwrap().event;
+    let asleep_event = Entry::from_str(asleep).unwrap().event;
+    let wakeup_event = Entry::from_str(wakeup).unwrap().event;
+
+    assert_eq!(Event::SwitchOver(10), guard_event);
+    assert_eq!(Event::SleepIn(5), asleep_event);
+    assert_eq!(Event::WakeUp(25), wakeup_event);
+}
+
+#[test]
+fn entry_round_trip() {
+    aoc_common::check::round_trip(|choices| {
+        let (hour, minute) = (choices.below(24), choices.below(60));
+        let event = match choices.below(3) {
+            0 => Event::SwitchOver(choices.below(usize::MAX)),
+            1 => Event::SleepIn(minute),
+            _ => Event::WakeUp(minute),
+        };
+        Entry {
+            timestamp: format!(
+                "[1518-{:02}-{:02} {:02}:{:02}]",
+                1 + choices.below(12),
+                1 + choices.below(28),
+                hour,
+                minute
+            ),
+            event,
+        }
+    });
+}
+
+static _TEST_INPUT: &str = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up";
+
+#[test]
+fn round_trip() {
+    for line in _TEST_INPUT.lines() {
+        assert_eq!(line, Entry::from_str(line).unwrap().to_string());
+    }
+}
+
+#[test]
+fn test_error_points_to_unsorted_line() {
+    let input = "[1518-11-01 00:25] wakes up
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:30] wakes up";
+
+    let error = Day04::parse(input, &()).unwrap_err();
+
+    assert_eq!(
+        PuzzleError::Shift {
+            span: Span::new(4, 1, "[1518-11-01 00:30]"),
+            error: ShiftError::AlreadyAwake,
+        },
+        error
+    );
+}
+
+#[test]
+fn test_parse_event_error() {
+    let error = Entry::from_str("[1518-11-01 00:05] falls aslep").unwrap_err();
+
+    assert_eq!(
+        PuzzleError::Parse {
+            span: Span::new(1, 20, "falls aslep"),
+            expected: "'Guard #ID begins shift', 'falls asleep' or 'wakes up'",
+        },
+        error
+    );
+}
+
+#[test]
+fn test_parse_minute_error() {
+    let error = Entry::from_str("[1518-11-01 00:75] falls asleep").unwrap_err();
+    let input = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:75] falls asleep
+[1518-11-01 00:80] wakes up";
+
+    assert_eq!(
+        PuzzleError::Parse {
+            span: Span::new(1, 16, "75"),
+            expected: "a minute below 60",
+        },
+        error
+    );
+    assert!(Day04::parse(input, &()).is_err());
+}
+
+#[test]
+fn test_parse_guard_error() {
+    let error = Entry::from_str("[1518-11-01 00:00] Guard #10 begins shift twice").unwrap_err();
+
+    assert_eq!(
+        PuzzleError::Parse {
+            span: Span::new(1, 20, "Guard #10 begins shift twice"),
+            expected: "'Guard #ID begins shift', 'falls asleep' or 'wakes up'",
+        },
+        error
+    );
+}
+
+#[test]
+fn test_full_example() {
+    let events = _TEST_INPUT
+        .lines()
+        .map(|line| Entry::from_str(line).map(|entry| entry.event))
+        .collect::<Result<Vec<Event>, _>>()
+        .unwrap();
+    let mut world = World::new();
+
+    world.process_sorted_events(&events).unwrap();
+    let (sleepiest_guard_id, amount_slept) = world.get_sleepiest_guard().unwrap();
+    let (sleepiest_minute, count) = world
+        .guards
+        .get(&sleepiest_guard_id)
+        .unwrap()
+        .sleepiest_minute()
+        .unwrap();
+
+    assert_eq!(sleepiest_guard_id, 10);
+    assert_eq!(amount_slept, 50);
+    assert_eq!(sleepiest_minute, 24);
+    assert_eq!(count, 2);
+}
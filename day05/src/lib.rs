@@ -0,0 +1,375 @@
+//! Day 5: Alchemical Reduction
+//!
+//! Reduces a polymer by removing adjacent units of the same type and opposite
+//! polarity, e.g. `aA`, until no more reactions are possible.
+
+extern crate aoc_common;
+
+use aoc_common::diagnostic::{Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::random::Rng;
+use aoc_common::{Answer, Json, Solution};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Solution of day 5 for the runners
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day5.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Polymer;
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = Improvement;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        Polymer::new(input.trim_end())
+    }
+
+    fn part1(polymer: &Self::Input, _params: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        Ok(polymer.reduce(None).len())
+    }
+
+    fn part2(polymer: &Self::Input, _params: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let alphabet = b'a'..=b'z';
+        Ok(alphabet
+            .map(|unit| Improvement {
+                removed: unit as char,
+                length: polymer.reduce(Some(unit as char)).len(),
+            })
+            .min_by_key(|improvement| improvement.length)
+            .expect("The alphabet is not empty"))
+    }
+}
+
+impl Generate for Day05 {
+    const SIZE: usize = 50_000;
+
+    /// A polymer of `size` units.
+    ///
+    /// Two in five units react with the last unit which did not react yet,
+    /// so reactions are nested like in the puzzle input, and about a fifth remains.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut polymer = String::with_capacity(size + 1);
+        let mut unreacted: Vec<Unit> = Vec::new();
+        for _ in 0..size {
+            let unit = match unreacted.last() {
+                Some(last) if rng.chance(2, 5) => {
+                    let opposite = if last.is_ascii_lowercase() {
+                        last.to_ascii_uppercase()
+                    } else {
+                        last.to_ascii_lowercase()
+                    };
+                    unreacted.pop();
+                    opposite
+                }
+                _ => {
+                    let unit = (b'a' + rng.below(26) as u8) as Unit;
+                    let unit = if rng.chance(1, 2) {
+                        unit.to_ascii_uppercase()
+                    } else {
+                        unit
+                    };
+                    unreacted.push(unit);
+                    unit
+                }
+            };
+            polymer.push(unit);
+        }
+        polymer.push('\n');
+        polymer
+    }
+}
+
+/// The length of the reduced polymer, after removing all units of one type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Improvement {
+    pub removed: Unit,
+    pub length: usize,
+}
+
+impl fmt::Display for Improvement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.length)
+    }
+}
+
+impl Answer for Improvement {
+    fn value(&self) -> Json {
+        Json::from(self.length)
+    }
+
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        vec![("removed_unit", Json::from(self.removed))]
+    }
+}
+
+/// Everything that can go wrong on day 5
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// The polymer contains a unit which is not an ascii letter
+    Parse { span: Span },
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span } => write!(
+                f,
+                "{}: expected an ascii letter as unit, found '{}'",
+                span, span.text
+            ),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span } => Some(span),
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span } => Some(span),
+        }
+    }
+}
+
+/// A unit is a letter, its case is the polarity.
+pub type Unit = char;
+/// A polymer consisting only of ascii alphabetic units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polymer(String);
+impl Polymer {
+    /// Fails if the polymer contains anything but ascii letters.
+    pub fn new<T: AsRef<str> + ?Sized>(s: &T) -> Result<Polymer, PuzzleError> {
+        let s = s.as_ref();
+        let non_ascii_alphabetic = s.char_indices().find(|(_, c)| !c.is_ascii_alphabetic());
+        if let Some((idx, c)) = non_ascii_alphabetic {
+            Err(PuzzleError::Parse {
+                span: Span::locate(s, &s[idx..idx + c.len_utf8()]),
+            })
+        } else {
+            Ok(Polymer(s.to_owned()))
+        }
+    }
+
+    // by new() we are guaranteed to contain only ascii alphabetic characters
+    /// Fully react the polymer, optionally removing all units of type `ignore_unit`
+    /// (regardless of polarity) beforehand.
+    pub fn reduce(&self, ignore_unit: Option<Unit>) -> ReducedPolymer {
+        let ignore_unit = ignore_unit.map(|unit| unit.to_ascii_lowercase());
+
+        let not_ignored_units = self
+            .0
+            .chars()
+            // if given, filter units matching the ignore_units from iterator
+            .filter(|c| {
+                !ignore_unit
+                    .map(|ignore| _eq_unit(*c, ignore))
+                    .unwrap_or(false)
+            });
+
+        let mut polymer = String::new();
+        for current in not_ignored_units {
+            if let Some(last) = polymer.chars().last() {
+                // remove last pushed unit, and ignore current unit, if they match
+                if current != last && _eq_unit(current, last) {
+                    polymer.pop();
+                    continue;
+                }
+            }
+            polymer.push(current);
+        }
+        ReducedPolymer(polymer)
+    }
+}
+
+fn _eq_unit(unit: Unit, other: Unit) -> bool {
+    unit.eq_ignore_ascii_case(&other)
+}
+
+impl FromStr for Polymer {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        Polymer::new(s)
+    }
+}
+impl fmt::Display for Polymer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A polymer in which no more reactions are possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReducedPolymer(String);
+
+impl ReducedPolymer {
+    /// Number of units left
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether all units reacted
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for ReducedPolymer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[test]
+fn test_failed_polymer() {
+    let poly_start = Polymer::new(">aAaasA");
+    let poly_center = Polymer::new("aAa!asA");
+    let poly_end = Polymer::new("aAaasAü");
+
+    assert!(poly_start.is_err());
+    assert!(poly_center.is_err());
+    assert!(poly_end.is_err());
+}
+
+#[test]
+fn test_new_polymer() {
+    let polymer = Polymer::new("aAa!asA");
+
+    assert!(polymer.is_err())
+}
+
+#[test]
+fn test_failed_polymer_span() {
+    let polymer = Polymer::new("aAaasAü");
+
+    assert_eq!(
+        Err(PuzzleError::Parse {
+            span: Span::new(1, 7, "ü")
+        }),
+        polymer
+    );
+}
+
+#[cfg(test)]
+fn random_polymer(choices: &mut aoc_common::check::Choices) -> Polymer {
+    // Few unit types, so that many units react
+    Polymer(choices.string(0..=60, "abcABC"))
+}
+
+#[test]
+fn test_polymer_round_trip() {
+    aoc_common::check::round_trip(random_polymer);
+}
+
+#[test]
+fn test_reduced_polymer_does_not_react() {
+    aoc_common::check::check(
+        |choices| {
+            (
+                random_polymer(choices),
+                *choices.choose(&[None, Some('a'), Some('B')]),
+            )
+        },
+        |(polymer, ignore_unit)| {
+            let reduced = polymer.reduce(*ignore_unit).to_string();
+            let units: Vec<Unit> = reduced.chars().collect();
+            if let Some(pair) = units
+                .windows(2)
+                .find(|pair| pair[0] != pair[1] && _eq_unit(pair[0], pair[1]))
+            {
+                return Err(format!(
+                    "'{}{}' still reacts in '{}'",
+                    pair[0], pair[1], reduced
+                ));
+            }
+            match ignore_unit {
+                Some(ignored) if units.iter().any(|unit| _eq_unit(*unit, *ignored)) => {
+                    Err(format!("'{}' is not removed from '{}'", ignored, reduced))
+                }
+                _ => Ok(()),
+            }
+        },
+    );
+}
+
+#[test]
+fn test_full_reduction() {
+    let polymer = Polymer::new("aA").unwrap();
+
+    let reduced = polymer.reduce(None);
+
+    assert_eq!(Polymer("aA".to_owned()), polymer);
+    assert_eq!(ReducedPolymer("".to_owned()), reduced);
+}
+
+#[test]
+fn test_recursive_reduction() {
+    let polymer = Polymer::new("abBA").unwrap();
+
+    let reduced = polymer.reduce(None);
+
+    assert_eq!(Polymer("abBA".to_owned()), polymer);
+    assert_eq!(ReducedPolymer("".to_owned()), reduced);
+}
+
+#[test]
+fn test_no_reduction() {
+    let polymer = Polymer::new("abAB").unwrap();
+
+    let reduced = polymer.reduce(None);
+
+    assert_eq!(Polymer("abAB".to_owned()), polymer);
+    assert_eq!(ReducedPolymer("abAB".to_owned()), reduced);
+}
+
+#[test]
+fn test_multi_no_reduction() {
+    let polymer = Polymer::new("aabAAB").unwrap();
+
+    let reduced = polymer.reduce(None);
+
+    assert_eq!(Polymer("aabAAB".to_owned()), polymer);
+    assert_eq!(ReducedPolymer("aabAAB".to_owned()), reduced)
+}
+
+#[test]
+fn test_example() {
+    let polymer = Polymer::new("dabAcCaCBAcCcaDA").unwrap();
+
+    let reduced = polymer.reduce(None);
+
+    assert_eq!(Polymer("dabAcCaCBAcCcaDA".to_owned()), polymer);
+    assert_eq!(ReducedPolymer("dabCBAcaDA".to_owned()), reduced)
+}
+
+#[test]
+fn test_example_reduce_ignoring_unit() {
+    let a_poly = Polymer::new("dabAcCaCBAcCcaDA").unwrap();
+    let b_poly = Polymer::new("dabAcCaCBAcCcaDA").unwrap();
+    let c_poly = Polymer::new("dabAcCaCBAcCcaDA").unwrap();
+    let d_poly = Polymer::new("dabAcCaCBAcCcaDA").unwrap();
+
+    let a_reduced = a_poly.reduce(Some('a'));
+    let b_reduced = b_poly.reduce(Some('b'));
+    let c_reduced = c_poly.reduce(Some('c'));
+    let d_reduced = d_poly.reduce(Some('d'));
+
+    assert_eq!(a_reduced.len(), 6);
+    assert_eq!(b_reduced.len(), 8);
+    assert_eq!(c_reduced.len(), 4);
+    assert_eq!(d_reduced.len(), 6);
+}
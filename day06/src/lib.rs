@@ -0,0 +1,423 @@
+//! Day 6: Chronal Coordinates
+//!
+//! Computes the manhattan distance of every location in the bounding box of the
+//! destinations to each of them, to find the largest finite area closest to a single
+//! destination, and the region close to all destinations, which may reach beyond it.
+
+extern crate aoc_common;
+
+use aoc_common::cli::UsageError;
+use aoc_common::diagnostic::{parse_lines, Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::params::{positive, unknown_option};
+use aoc_common::random::Rng;
+use aoc_common::render::{Color, Render};
+use aoc_common::{Grid2D, Params, Solution};
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Solution of day 6 for the runners
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day6.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = SubGrid;
+    type Params = Region;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        let destinations: Result<Vec<_>, _> = parse_lines(input, FromStr::from_str).collect();
+        SubGrid::new(&destinations?)
+    }
+
+    fn part1(grid: &Self::Input, _params: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        let finite_areas = grid.finite_area();
+        finite_areas
+            .values()
+            .max()
+            .cloned()
+            .ok_or(PuzzleError::NoFiniteArea)
+    }
+
+    fn part2(grid: &Self::Input, region: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        Ok(grid.area_within_total_distance(region.total_distance))
+    }
+}
+
+/// The region of part 2 contains all locations whose summed distance
+/// to all destinations is less than `total_distance`, 10000 in the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub total_distance: Distance,
+}
+
+impl Default for Region {
+    fn default() -> Self {
+        Region {
+            total_distance: 10_000,
+        }
+    }
+}
+
+impl Params for Region {
+    const USAGE: &'static str = "[--total-distance N]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--total-distance" => self.total_distance = positive(name, value)?,
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+impl Generate for Day06 {
+    const SIZE: usize = 50;
+
+    /// `size` distinct destinations, but at least 5, spread over about 50 by 50 locations each.
+    ///
+    /// One destination is surrounded by four others in each direction, so its area is finite.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let extent = 50 * ((size as f64).sqrt() as usize).max(1);
+        let (center, radius) = (extent / 2, extent / 8 + rng.below(extent / 8));
+        let mut destinations = vec![
+            Destination {
+                x: center,
+                y: center,
+            },
+            Destination {
+                x: center - radius,
+                y: center,
+            },
+            Destination {
+                x: center + radius,
+                y: center,
+            },
+            Destination {
+                x: center,
+                y: center - radius,
+            },
+            Destination {
+                x: center,
+                y: center + radius,
+            },
+        ];
+        let mut seen: HashSet<_> = destinations.iter().cloned().collect();
+        while destinations.len() < size {
+            let destination = Destination {
+                x: rng.below(extent + 1),
+                y: rng.below(extent + 1),
+            };
+            if seen.insert(destination.clone()) {
+                destinations.push(destination);
+            }
+        }
+        rng.shuffle(&mut destinations);
+
+        destinations
+            .iter()
+            .map(|destination| format!("{}\n", destination))
+            .collect()
+    }
+}
+
+impl Render for Day06 {
+    /// The area closest to each destination in its own color, darkened if it is infinite.
+    /// Locations tied between destinations are black, the destinations white,
+    /// and the region of part 2 is lightened.
+    fn render(grid: &Self::Input, region: &Self::Params) -> Result<Grid2D<Color>, Self::Error> {
+        // Every location knows the distance to all destinations, and there is at least one
+        let mut destinations: Vec<_> = grid.distances.cells()[0].0.keys().collect();
+        // Sorted, so each destination keeps its color however the input is ordered
+        destinations.sort_by_key(|d| (d.x, d.y));
+        let colors: HashMap<_, _> = destinations
+            .into_iter()
+            .enumerate()
+            .map(|(index, destination)| (destination, Color::distinct(index)))
+            .collect();
+
+        Ok(grid.distances.map(|(distances, closest)| {
+            let area = match closest {
+                Some(destination) if distances[destination] == 0 => Color::WHITE,
+                Some(destination) if grid.bordering.contains(destination) => {
+                    colors[destination].mix(Color::BLACK, 0.5)
+                }
+                Some(destination) => colors[destination],
+                None => Color::BLACK,
+            };
+            if distances.values().sum::<Distance>() < region.total_distance {
+                area.mix(Color::WHITE, 0.4)
+            } else {
+                area
+            }
+        }))
+    }
+}
+
+/// Everything that can go wrong on day 6
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A line is not a destination
+    Parse { span: Span, expected: &'static str },
+    /// There are no destinations
+    NoDestinations,
+    /// The areas of all destinations are infinite
+    NoFiniteArea,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span, expected } => {
+                write!(f, "{}: expected {}, found '{}'", span, expected, span.text)
+            }
+            PuzzleError::NoDestinations => write!(f, "Destinations may not be empty!"),
+            PuzzleError::NoFiniteArea => write!(f, "Could not determine largest area"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+}
+
+/// Manhattan distance
+pub type Distance = usize;
+/// Coordinates given in the puzzle input
+#[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Hash)]
+pub struct Destination {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl FromStr for Destination {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let parts: Result<Vec<usize>, _> = s
+            .split(',')
+            .map(|p| {
+                let p = p.trim();
+                p.parse().map_err(|_| PuzzleError::Parse {
+                    span: Span::locate(s, p),
+                    expected: "a coordinate",
+                })
+            })
+            .collect();
+        match parts?.as_slice() {
+            [x, y] => Ok(Destination { x: *x, y: *y }),
+            _ => Err(PuzzleError::Parse {
+                span: Span::locate(s, s),
+                expected: "coordinates like '1, 6'",
+            }),
+        }
+    }
+}
+
+/// The coordinates as in the puzzle input, e.g. `1, 6`
+impl fmt::Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {}", self.x, self.y)
+    }
+}
+
+/// The locations from the origin up to the destination furthest right and down,
+/// with the distance to each destination, and the single closest destination if there is one.
+#[derive(Clone, Debug)]
+pub struct SubGrid {
+    distances: Grid2D<(HashMap<Destination, Distance>, Option<Destination>)>,
+    bordering: HashSet<Destination>,
+}
+
+impl SubGrid {
+    /// Fails if there are no destinations.
+    pub fn new(destinations: &[Destination]) -> Result<Self, PuzzleError> {
+        if destinations.is_empty() {
+            return Err(PuzzleError::NoDestinations);
+        }
+        let size_x = destinations
+            .iter()
+            .map(|d| d.x)
+            .max()
+            .expect("Non empty destinations guarantee a value here!");
+        let size_y = destinations
+            .iter()
+            .map(|d| d.y)
+            .max()
+            .expect("Non empty destinations guarantee a value here!");
+
+        let distances = Grid2D::from_fn(size_x + 1, size_y + 1, |x, y| {
+            let destination_distances: HashMap<_, _> = destinations
+                .iter()
+                .map(|d| (d.to_owned(), d.manhattan_distance(x as usize, y as usize)))
+                .collect();
+            let min_distance = destination_distances
+                .iter()
+                .min_by_key(|d| d.1)
+                .expect("At least one destination, means at least a minimum distance");
+            let min_count = destination_distances
+                .iter()
+                .filter(|d| *d.1 == *min_distance.1)
+                .count();
+            let closest = match min_count {
+                1 => Some(min_distance.0.to_owned()),
+                _ => None,
+            };
+            (destination_distances, closest)
+        });
+        let bordering = distances
+            .iter()
+            .filter(|((x, y), _)| distances.on_edge(*x, *y))
+            .filter_map(|(_, (_, closest))| closest.to_owned())
+            .collect();
+        Ok(Self {
+            distances,
+            bordering,
+        })
+    }
+
+    /// Size of the area closest to each destination, for all destinations whose area
+    /// does not reach the border, and therefore would be infinite.
+    pub fn finite_area(&self) -> HashMap<Destination, usize> {
+        self.distances
+            .cells()
+            .iter()
+            .filter_map(|d| d.to_owned().1.or(None))
+            .filter(|d| !self.bordering.contains(d))
+            .fold(HashMap::new(), |mut acc, item| {
+                acc.entry(item).and_modify(|entry| *entry += 1).or_insert(1);
+                acc
+            })
+    }
+
+    /// Number of locations whose summed distance to all destinations is less than `limit`,
+    /// saturating at `usize::MAX`.
+    ///
+    /// The region may reach beyond the grid, but not by more than `(limit - 1) / n` for `n`
+    /// destinations, as a location further away along an axis is at least `limit` away in total.
+    pub fn area_within_total_distance(&self, limit: Distance) -> usize {
+        // Every location knows the distance to all destinations, and there is at least one
+        let destinations: Vec<&Destination> = self.distances.cells()[0].0.keys().collect();
+        let sorted = |axis: &dyn Fn(&Destination) -> usize| {
+            let mut coordinates: Vec<i128> = destinations.iter().map(|d| axis(d) as i128).collect();
+            coordinates.sort_unstable();
+            coordinates
+        };
+        let (xs, ys) = (sorted(&|d| d.x), sorted(&|d| d.y));
+        let margin = (limit.saturating_sub(1) / destinations.len()) as i128;
+        let (from_y, to_y) = (ys[0] - margin, ys[ys.len() - 1] + margin);
+
+        // The distances along both axes add up, so each column is counted on its own
+        let area: i128 = (xs[0] - margin..=xs[xs.len() - 1] + margin)
+            .map(|x| positions_below(&ys, limit as i128 - axis_distance(&xs, x), from_y, to_y))
+            .sum();
+        usize::try_from(area).unwrap_or(usize::MAX)
+    }
+}
+
+/// The summed distance along an axis from `at` to all coordinates.
+fn axis_distance(coordinates: &[i128], at: i128) -> i128 {
+    coordinates.iter().map(|c| (c - at).abs()).sum()
+}
+
+/// Number of positions from `from` to `to` whose summed distance to the sorted coordinates
+/// is below `bound`. The distance falls up to the median and rises after it, so these
+/// positions are a single range around the median, found by binary search on both sides.
+fn positions_below(coordinates: &[i128], bound: i128, from: i128, to: i128) -> i128 {
+    let median = coordinates[coordinates.len() / 2].max(from).min(to);
+    let below = |at: i128| axis_distance(coordinates, at) < bound;
+    if !below(median) {
+        return 0;
+    }
+    let (mut low, mut first) = (from, median);
+    while low < first {
+        let middle = low + (first - low) / 2;
+        if below(middle) {
+            first = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    let (mut last, mut high) = (median, to);
+    while last < high {
+        let middle = last + (high - last + 1) / 2;
+        if below(middle) {
+            last = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+    last - first + 1
+}
+
+impl Destination {
+    /// Manhattan distance from this destination to `x`,`y`.
+    pub fn manhattan_distance(&self, x: usize, y: usize) -> usize {
+        let x_dist = x.abs_diff(self.x);
+        let y_dist = y.abs_diff(self.y);
+        x_dist + y_dist
+    }
+}
+
+// Due to lack of time, today there are no tests.
+
+#[test]
+fn test_destination_round_trip() {
+    aoc_common::check::round_trip(|choices| Destination {
+        x: choices.below(usize::MAX),
+        y: choices.below(usize::MAX),
+    });
+}
+
+#[test]
+fn test_area_within_total_distance() {
+    let destinations: Vec<Destination> = ["1, 1", "1, 6", "8, 3", "3, 4", "5, 5", "8, 9"]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+    let grid = SubGrid::new(&destinations).unwrap();
+    let brute_force = |limit: i64| {
+        let total = |x: i64, y: i64| -> i64 {
+            destinations
+                .iter()
+                .map(|d| (d.x as i64 - x).abs() + (d.y as i64 - y).abs())
+                .sum()
+        };
+        let range = -limit..=limit;
+        range
+            .clone()
+            .flat_map(|x| range.clone().map(move |y| (x, y)))
+            .filter(|(x, y)| total(*x, *y) < limit)
+            .count()
+    };
+
+    assert_eq!(16, grid.area_within_total_distance(32));
+    assert_eq!(0, grid.area_within_total_distance(1));
+    // Reaching far beyond the destinations, which are within 10 of the origin
+    assert_eq!(brute_force(300), grid.area_within_total_distance(300));
+    assert_eq!(brute_force(97), grid.area_within_total_distance(97));
+}
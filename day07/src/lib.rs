@@ -0,0 +1,582 @@
+//! Day 7: The Sum of Its Parts
+//!
+//! Orders the steps of the sleigh assembly instructions by their dependencies,
+//! and schedules them on several workers working in parallel.
+
+use aoc_common::cli::UsageError;
+use aoc_common::diagnostic::{parse_lines, Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::input::{read_lines, StreamError};
+use aoc_common::params::{number, positive, unknown_option};
+use aoc_common::random::Rng;
+use aoc_common::{Answer, Json, Params, Solution};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// Solution of day 7 for the runners
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day7.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Nodes;
+    type Params = Crew;
+    type Answer1 = String;
+    type Answer2 = Schedule;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        let dependencies: Result<Vec<Dependency>, _> =
+            parse_lines(input, FromStr::from_str).collect();
+        Ok(From::from(dependencies?.as_slice()))
+    }
+
+    fn read<R: BufRead>(
+        reader: R,
+        _params: &Self::Params,
+    ) -> Result<Self::Input, StreamError<Self::Error>> {
+        let dependencies: Result<Vec<Dependency>, _> = read_dependencies(reader).collect();
+        Ok(From::from(dependencies?.as_slice()))
+    }
+
+    fn part1(nodes: &Self::Input, _params: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        Ok(nodes.resolve())
+    }
+
+    fn part2(nodes: &Self::Input, crew: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let (order, duration) = nodes.par_resolve(crew.workers, crew.base_duration)?;
+        Ok(Schedule {
+            order,
+            workers: crew.workers,
+            duration,
+        })
+    }
+}
+
+/// The workers of part 2, 5 in the puzzle, and the seconds each step takes
+/// in addition to its position in the alphabet, 60 in the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crew {
+    pub workers: usize,
+    pub base_duration: usize,
+}
+
+impl Default for Crew {
+    fn default() -> Self {
+        Crew {
+            workers: 5,
+            base_duration: 60,
+        }
+    }
+}
+
+impl Params for Crew {
+    const USAGE: &'static str = "[--workers N] [--base-duration SECONDS]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--workers" => self.workers = positive(name, value)?,
+            "--base-duration" => self.base_duration = number(name, value)?,
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+impl Generate for Day07 {
+    const SIZE: usize = 26;
+
+    /// The instructions for `size` steps, at least 2 and at most 26 as steps are letters.
+    ///
+    /// The steps are put in a random order, and every step depends on at least one
+    /// of the steps before it, about a third of them on average, so there are no cycles.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut steps: Vec<char> = ('A'..='Z').collect();
+        rng.shuffle(&mut steps);
+        steps.truncate(size.max(2));
+
+        let mut dependencies = Vec::new();
+        for (i, by) in steps.iter().enumerate().skip(1) {
+            let first = rng.below(i);
+            for (j, needed) in steps[..i].iter().enumerate() {
+                if j == first || rng.chance(3, 10) {
+                    dependencies.push(Dependency {
+                        needed: *needed,
+                        by: *by,
+                    });
+                }
+            }
+        }
+        rng.shuffle(&mut dependencies);
+
+        dependencies
+            .iter()
+            .map(|dependency| format!("{}\n", dependency))
+            .collect()
+    }
+}
+
+/// The steps completed by several workers in parallel,
+/// the answer is the number of seconds it takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// The order in which the steps are completed
+    pub order: String,
+    pub workers: usize,
+    pub duration: usize,
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.duration)
+    }
+}
+
+impl Answer for Schedule {
+    fn value(&self) -> Json {
+        Json::from(self.duration)
+    }
+
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("order", Json::from(self.order.as_str())),
+            ("workers", Json::from(self.workers)),
+        ]
+    }
+}
+
+/// Everything that can go wrong on day 7
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A line is not an instruction
+    Parse { span: Span, expected: &'static str },
+    /// The steps can not be completed without any workers
+    NoWorkers,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span, expected } => {
+                write!(f, "{}: expected {}, found '{}'", span, expected, span.text)
+            }
+            PuzzleError::NoWorkers => write!(f, "Need at least one worker to complete project"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            PuzzleError::NoWorkers => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            PuzzleError::NoWorkers => None,
+        }
+    }
+}
+
+/// The dependencies between steps, parsed one line at a time while reading them.
+pub fn read_dependencies<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<Dependency, StreamError<PuzzleError>>> {
+    read_lines(reader, FromStr::from_str)
+}
+
+/// Step `needed` must be finished before step `by` can begin.
+#[derive(Debug, Clone, Eq, Hash, PartialOrd, PartialEq)]
+pub struct Dependency {
+    pub by: Node,
+    pub needed: Node,
+}
+
+/// A step, named by an uppercase letter
+pub type Node = char;
+type Dependencies = HashMap<Node, Vec<Node>>;
+type Worker = Option<(char, usize)>;
+/// All steps, with the steps they depend on.
+pub struct Nodes(Dependencies);
+
+const USIZE_OFFSET: usize = 64;
+
+impl From<&[Dependency]> for Nodes {
+    fn from(dependencies: &[Dependency]) -> Self {
+        let deps = dependencies
+            .iter()
+            .fold(HashMap::new(), |mut acc: Dependencies, item| {
+                let Dependency { needed, by } = item;
+                // Add Dependency
+                acc.entry(*by)
+                    .and_modify(|deps| deps.push(*needed))
+                    .or_insert(vec![*needed]);
+                // Add Other node to Nodes
+                acc.entry(*needed).or_default();
+                acc
+            });
+        Nodes(deps)
+    }
+}
+
+impl Nodes {
+    /// Steps without any dependencies, in no particular order.
+    pub fn get_root_steps(&self) -> Vec<Node> {
+        self.0
+            .iter()
+            .filter_map(|d| if d.1.is_empty() { Some(*d.0) } else { None })
+            .collect()
+    }
+
+    /// The order in which the steps are completed by a single worker.
+    /// Of all available steps the alphabetically first one is completed first.
+    pub fn resolve(&self) -> String {
+        let mut dependencies = self.0.clone();
+        let mut candidates = self.get_root_steps();
+        let mut sequence = String::new();
+
+        while !candidates.is_empty() {
+            // Inverse alphabetical order allows to pop() next candidate
+            candidates.sort_by(|a, b| b.cmp(a));
+
+            // last candidate will be solved in this round
+            let current = candidates.pop().expect("Candidates is never empty here");
+            sequence.push(current);
+
+            // For nodes with not yet satisfied dependencies
+            for (step, step_dependencies) in dependencies.iter_mut().filter(|d| !d.1.is_empty()) {
+                // Remove all occurrences of the current solved step from our dependencies
+                while let Some(idx) = step_dependencies.iter().position(|c| *c == current) {
+                    step_dependencies.remove(idx);
+                }
+                // No dependencies for current node left, so it's ready for work
+                if step_dependencies.is_empty() {
+                    candidates.push(*step);
+                }
+            }
+        }
+        sequence
+    }
+
+    /// The order in which the steps are completed by `workers` working in parallel,
+    /// and the number of seconds it takes.
+    ///
+    /// Each step takes `base_duration` seconds plus its position in the alphabet.
+    ///
+    /// ```
+    /// use day07::{Dependency, Nodes};
+    ///
+    /// let dependencies = [
+    ///     Dependency { needed: 'A', by: 'B' },
+    ///     Dependency { needed: 'A', by: 'C' },
+    /// ];
+    /// let nodes = Nodes::from(&dependencies[..]);
+    ///
+    /// assert_eq!(("ABC".to_owned(), 4), nodes.par_resolve(2, 0).unwrap());
+    /// ```
+    pub fn par_resolve(
+        &self,
+        workers: usize,
+        base_duration: usize,
+    ) -> Result<(String, usize), PuzzleError> {
+        if workers < 1 {
+            return Err(PuzzleError::NoWorkers);
+        }
+
+        let mut dependencies = self.0.clone();
+        let mut candidates = self.get_root_steps();
+
+        // Inverse alphabetical order allows to pop() next candidate
+        candidates.sort_by(|a, b| b.cmp(a));
+
+        let mut epoch = 0;
+        let mut workers: Vec<Worker> = vec![None; workers];
+        let mut sequence = String::new();
+
+        loop {
+            for worker in &mut workers.iter_mut() {
+                if let Some(busy_worker) = worker {
+                    let (current, time_left) = busy_worker;
+                    *time_left -= 1;
+
+                    // The current step is completed during this iteration
+                    if *time_left == 0 {
+                        // Remove the dependency of the completed step from all nodes depending on it
+                        for deps in dependencies.iter_mut().filter(|d| !d.1.is_empty()) {
+                            while let Some(idx) = deps.1.iter().position(|c| *c == *current) {
+                                deps.1.remove(idx);
+                            }
+                            // If this was the last dependency for this specific node,
+                            // it becomes eligible to be completed
+                            if deps.1.is_empty() {
+                                candidates.push(*deps.0);
+                                // Inverse alphabetical order allows to pop() next candidate
+                                candidates.sort_by(|a, b| b.cmp(a));
+                            }
+                        }
+
+                        sequence.push(*current);
+                        *worker = None;
+                    }
+                }
+            }
+            // All workers must have finished their work for this step,
+            // before new work is assigned to workers without work.
+            for worker in &mut workers {
+                if worker.is_none() {
+                    if let Some(next) = candidates.pop() {
+                        let duration = next as usize + base_duration - USIZE_OFFSET;
+                        *worker = Some((next, duration))
+                    }
+                }
+            }
+
+            // If solved, avoid advancing and counting 1 round too many
+            if workers.iter().any(Option::is_some)
+                || dependencies.iter().any(|deps| !deps.1.is_empty())
+            {
+                epoch += 1;
+            } else {
+                break;
+            }
+        }
+        Ok((sequence, epoch))
+    }
+}
+
+// Format:
+// Step C must be finished before step A can begin.
+impl FromStr for Dependency {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let instruction = || {
+            let rest = s.trim().strip_prefix("Step ")?;
+            let (needed, rest) = rest.split_once(" must be finished before step ")?;
+            let by = rest.strip_suffix(" can begin.")?;
+            Some((needed.trim(), by.trim()))
+        };
+        let step = |node: &str| {
+            let mut chars = node.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => Ok(c.to_ascii_uppercase()),
+                _ => Err(PuzzleError::Parse {
+                    span: Span::locate(s, node),
+                    expected: "a step named by a single letter",
+                }),
+            }
+        };
+
+        let (needed, by) = instruction().ok_or_else(|| PuzzleError::Parse {
+            span: Span::locate(s, s),
+            expected: "an instruction like 'Step C must be finished before step A can begin.'",
+        })?;
+        Ok(Self {
+            needed: step(needed)?,
+            by: step(by)?,
+        })
+    }
+}
+
+/// The instruction as in the puzzle input
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Step {} must be finished before step {} can begin.",
+            self.needed, self.by
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use aoc_common::check::{self, Choices};
+
+    /// Dependencies without cycles between up to 8 steps, as every step depends
+    /// only on steps before it in a random order.
+    fn acyclic_dependencies(choices: &mut Choices) -> Vec<Dependency> {
+        let mut steps: Vec<Node> = ('A'..='H').collect();
+        choices.shuffle(&mut steps);
+        choices.vec(1..=20, |choices| {
+            let by = 1 + choices.below(steps.len() - 1);
+            Dependency {
+                needed: steps[choices.below(by)],
+                by: steps[by],
+            }
+        })
+    }
+
+    /// Whether `order` contains every step once, and every step after the steps it depends on.
+    fn respects(dependencies: &[Dependency], order: &str) -> Result<(), String> {
+        let mut steps: Vec<Node> = dependencies
+            .iter()
+            .flat_map(|dependency| vec![dependency.needed, dependency.by])
+            .collect();
+        steps.sort_unstable();
+        steps.dedup();
+        let mut ordered: Vec<Node> = order.chars().collect();
+        ordered.sort_unstable();
+        if ordered != steps {
+            return Err(format!("'{}' does not contain every step once", order));
+        }
+
+        let position = |step| order.find(step).expect("Every step is in the order");
+        match dependencies
+            .iter()
+            .find(|dependency| position(dependency.needed) > position(dependency.by))
+        {
+            Some(dependency) => Err(format!("'{}' does not respect '{}'", order, dependency)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn test_dependency_round_trip() {
+        check::round_trip(|choices| Dependency {
+            needed: *choices.choose(&['A', 'B', 'Y', 'Z']),
+            by: (b'A' + choices.below(26) as u8) as char,
+        });
+    }
+
+    #[test]
+    fn test_resolve_respects_dependencies() {
+        check::check(acyclic_dependencies, |dependencies| {
+            respects(
+                dependencies,
+                &Nodes::from(dependencies.as_slice()).resolve(),
+            )
+        });
+    }
+
+    #[test]
+    fn test_par_resolve_respects_dependencies() {
+        check::check(
+            |choices| (acyclic_dependencies(choices), 1 + choices.below(3)),
+            |(dependencies, workers)| {
+                let nodes = Nodes::from(dependencies.as_slice());
+                let (order, _) = nodes.par_resolve(*workers, 0).map_err(|e| e.to_string())?;
+                respects(dependencies, &order)
+            },
+        );
+    }
+
+    #[test]
+    fn test_parse_deps() {
+        let dependencies: Result<Vec<Dependency>, _> =
+            INPUT.iter().map(|s| FromStr::from_str(s)).collect();
+
+        assert!(dependencies.is_ok());
+        assert_eq!(NODES, dependencies.unwrap().as_slice())
+    }
+
+    #[test]
+    fn test_parse_deps_error() {
+        let input = "Step C must be finished before step A can begin.\n\
+                     Step C must be finished before step FF can begin.";
+
+        let dependencies: Result<Vec<Dependency>, _> =
+            parse_lines(input, FromStr::from_str).collect();
+
+        assert_eq!(
+            Err(PuzzleError::Parse {
+                span: Span::new(2, 37, "FF"),
+                expected: "a step named by a single letter",
+            }),
+            dependencies
+        );
+    }
+
+    #[test]
+    fn test_example_root() {
+        let dependencies: Result<Vec<Dependency>, _> =
+            INPUT.iter().map(|s| FromStr::from_str(s)).collect();
+
+        let dependencies = dependencies.unwrap();
+        let nodes: Nodes = From::from(dependencies.as_slice());
+
+        let root = nodes.get_root_steps();
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0], 'C');
+    }
+
+    #[test]
+    fn test_example_resolve() {
+        let dependencies: Result<Vec<Dependency>, _> =
+            INPUT.iter().map(|s| FromStr::from_str(s)).collect();
+
+        let dependencies = dependencies.unwrap();
+        let nodes: Nodes = From::from(dependencies.as_slice());
+
+        let sequence = nodes.resolve();
+
+        assert_eq!("CABDFE", sequence);
+    }
+
+    #[test]
+    fn test_example_par_resolve() {
+        let dependencies: Result<Vec<Dependency>, _> =
+            INPUT.iter().map(|s| FromStr::from_str(s)).collect();
+
+        let dependencies = dependencies.unwrap();
+        let nodes: Nodes = From::from(dependencies.as_slice());
+
+        let (sequence, time) = nodes.par_resolve(2, 0).unwrap();
+
+        assert_eq!("CABFDE", sequence);
+        assert_eq!(15, time);
+    }
+
+    static INPUT: &[&str] = &[
+        "Step C must be finished before step A can begin.",
+        "Step C must be finished before step F can begin.",
+        "Step A must be finished before step B can begin.",
+        "Step A must be finished before step D can begin.",
+        "Step B must be finished before step E can begin.",
+        "Step D must be finished before step E can begin.",
+        "Step F must be finished before step E can begin.",
+    ];
+
+    static NODES: &[Dependency] = &[
+        Dependency {
+            by: 'A',
+            needed: 'C',
+        },
+        Dependency {
+            by: 'F',
+            needed: 'C',
+        },
+        Dependency {
+            by: 'B',
+            needed: 'A',
+        },
+        Dependency {
+            by: 'D',
+            needed: 'A',
+        },
+        Dependency {
+            by: 'E',
+            needed: 'B',
+        },
+        Dependency {
+            by: 'E',
+            needed: 'D',
+        },
+        Dependency {
+            by: 'E',
+            needed: 'F',
+        },
+    ];
+}
@@ -0,0 +1,373 @@
+//! Day 8: Memory Maneuver
+//!
+//! Parses the license file, a tree of nodes given as a flat list of numbers,
+//! and computes the sum of all metadata and the value of the root node.
+
+use aoc_common::diagnostic::{Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::input::{read_words, StreamError};
+use aoc_common::random::Rng;
+use aoc_common::Solution;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+
+/// Solution of day 8 for the runners
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day8.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Node;
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        let numbers: Result<Vec<usize>, _> = input
+            .split_whitespace()
+            .map(|number| {
+                number.parse().map_err(|_| PuzzleError::Parse {
+                    span: Span::locate(input, number),
+                })
+            })
+            .collect();
+
+        parse_nodes(numbers?)
+    }
+
+    fn read<R: BufRead>(
+        reader: R,
+        _params: &Self::Params,
+    ) -> Result<Self::Input, StreamError<Self::Error>> {
+        // The first invalid number ends the list, and is reported instead of the incomplete tree
+        let mut error = None;
+        let root = {
+            let mut numbers =
+                read_numbers(reader).map_while(|number| number.map_err(|e| error = Some(e)).ok());
+            let root = parse_nodes(numbers.by_ref());
+            // Numbers after the tree are not part of it, but must still be numbers
+            numbers.for_each(drop);
+            root
+        };
+        match error {
+            Some(error) => Err(error),
+            None => Ok(root?),
+        }
+    }
+
+    fn part1(root: &Self::Input, _params: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        Ok(root.metadata_sum())
+    }
+
+    fn part2(root: &Self::Input, _params: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        Ok(root.value())
+    }
+}
+
+impl Generate for Day08 {
+    const SIZE: usize = 2000;
+
+    /// A license tree of `size` nodes, each adopted by a random earlier node.
+    ///
+    /// Every node has 1 to 10 metadata entries between 1 and 9,
+    /// which may also refer to children the node does not have.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut children = vec![Vec::new(); size];
+        for node in 1..size {
+            children[rng.below(node)].push(node);
+        }
+
+        // Depth first, the children of a node follow its header
+        let mut numbers = Vec::new();
+        // The metadata count is known once the header is written
+        let mut stack = vec![(0, None)];
+        while let Some((node, metadata_count)) = stack.pop() {
+            match metadata_count {
+                Some(count) => numbers.extend((0..count).map(|_| 1 + rng.below(9))),
+                None => {
+                    let count = 1 + rng.below(10);
+                    numbers.push(children[node].len());
+                    numbers.push(count);
+                    stack.push((node, Some(count)));
+                    stack.extend(children[node].iter().rev().map(|child| (*child, None)));
+                }
+            }
+        }
+
+        let numbers: Vec<_> = numbers.iter().map(ToString::to_string).collect();
+        numbers.join(" ") + "\n"
+    }
+}
+
+/// Everything that can go wrong on day 8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// The license contains something other than a number
+    Parse { span: Span },
+    /// The license ends before the tree is complete
+    Truncated { missing: &'static str },
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span } => {
+                write!(f, "{}: expected a number, found '{}'", span, span.text)
+            }
+            PuzzleError::Truncated { missing } => {
+                write!(f, "The license ends before the {} of a node", missing)
+            }
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span } => Some(span),
+            PuzzleError::Truncated { .. } => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span } => Some(span),
+            PuzzleError::Truncated { .. } => None,
+        }
+    }
+}
+
+/// A node of the license tree.
+#[derive(Clone, Debug)]
+pub struct Node {
+    child_count: usize,
+    children: Vec<Node>,
+    metadata_count: usize,
+    metadata: Vec<usize>,
+}
+
+impl Node {
+    fn new(child_count: usize, metadata_count: usize) -> Self {
+        Node {
+            child_count,
+            children: Vec::new(),
+            metadata_count,
+            metadata: Vec::new(),
+        }
+    }
+
+    /// The child nodes, in order.
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// The metadata entries of this node, not including the children.
+    pub fn metadata(&self) -> &[usize] {
+        &self.metadata
+    }
+
+    /// Sum of the metadata of this node and all its descendants.
+    pub fn metadata_sum(&self) -> usize {
+        // Sum own metadata with all child node metadata sum
+        let own: usize = self.metadata.iter().sum();
+        let child_sum: usize = self.children.iter().map(|c| c.metadata_sum()).sum();
+        child_sum + own
+    }
+
+    /// Sum of the metadata for leaf nodes, otherwise the sum of the values
+    /// of the children referenced by the metadata entries (starting at 1).
+    pub fn value(&self) -> usize {
+        // The value of Nodes with no children equals the sum of the metadata
+        if self.child_count == 0 {
+            self.metadata.iter().sum()
+
+        // The value of Nodes with children equals to The Sum of the child nodes values,
+        // where child nodes are referred by the metadata entries used as index
+        } else {
+            self.metadata
+                .iter()
+                .map(|data| {
+                    // 1 refers to first entry, ...
+                    let idx = data - 1;
+                    // ignore non existing referenced children
+                    self.children
+                        .get(idx)
+                        .map(|child| child.value())
+                        .unwrap_or(0)
+                })
+                .sum()
+        }
+    }
+}
+
+enum State {
+    NewNode,
+    ReadMetadata,
+    AddChild,
+    Done,
+}
+
+// NewNode -> ReadMetadata: if no Children, read nodes metadata immediately
+// NewNode -> NewNode:      push next child onto stack
+
+// ReadMetadata -> AddChild: Reading Metadata completes a Node
+// ReadMetadata -> Done:     if we complete the outermost Node, we're done
+
+// AddChild -> ReadMetadata: if last child of parent added, read parent metadata
+// AddChild -> NewNode:      else push next child node onto stack
+
+/// The numbers of the license file, parsed one at a time while reading them.
+pub fn read_numbers<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<usize, StreamError<PuzzleError>>> {
+    read_words(reader).map(|word| {
+        let span = word.map_err(StreamError::Read)?;
+        match span.text.parse() {
+            Ok(number) => Ok(number),
+            Err(_) => Err(StreamError::from(PuzzleError::Parse { span })),
+        }
+    })
+}
+
+/// Parse the tree from the flat list of numbers: the child count,
+/// the metadata count, the children and then the metadata of each node.
+pub fn parse_nodes<I: IntoIterator<Item = usize>>(input: I) -> Result<Node, PuzzleError> {
+    let mut state: State = State::NewNode;
+    let mut cursor = input.into_iter();
+    let mut stack = Vec::new();
+    loop {
+        match state {
+            State::NewNode => {
+                // We must be able to read at least both count values.
+                if let Some(child_count) = cursor.next() {
+                    if let Some(metadata_count) = cursor.next() {
+                        let new_node = Node::new(child_count, metadata_count);
+                        stack.push(new_node);
+
+                        // Keep pushing new nodes unless we encounter a leaf node
+                        if child_count == 0 {
+                            state = State::ReadMetadata
+                        } else {
+                            state = State::NewNode
+                        }
+                    } else {
+                        return Err(PuzzleError::Truncated {
+                            missing: "metadata count",
+                        });
+                    }
+                } else {
+                    return Err(PuzzleError::Truncated {
+                        missing: "child count",
+                    });
+                }
+            }
+            State::ReadMetadata => {
+                // Read n items as metadata for the current stack frame
+                // There is always the node which was pushed last
+                let last = stack.last_mut().expect("Can't read metadata for nothing");
+                while last.metadata.len() < last.metadata_count {
+                    if let Some(data) = cursor.next() {
+                        last.metadata.push(data);
+                    } else {
+                        return Err(PuzzleError::Truncated {
+                            missing: "metadata",
+                        });
+                    }
+                }
+                // Reading metadata completes a Node
+                // if this was the last stack frame, we're done
+                // otherwise we must add the current frame to its parent
+                if stack.len() > 1 {
+                    state = State::AddChild
+                } else {
+                    state = State::Done
+                }
+            }
+            State::AddChild => {
+                // We're guaranteed the the last two items on the stack must be parent & child
+                let child = stack.pop().expect("Could not get child node.");
+                let parent = stack.last_mut().expect("Could not get parent node.");
+                // The previous top of the stack becomes child of the new top of the stack
+                parent.children.push(child);
+
+                // If we complete the children of the current stack frame
+                // we can start reading metadata for it
+                // otherwise we need to keep adding child nodes
+                if parent.children.len() == parent.child_count {
+                    state = State::ReadMetadata
+                } else {
+                    state = State::NewNode
+                }
+            }
+            State::Done => {
+                assert_eq!(stack.len(), 1, "More than 1 root left.");
+                return Ok(stack.pop().unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_example() {
+    let input = &[2, 3, 0, 3, 10, 11, 12, 1, 1, 0, 1, 99, 2, 1, 1, 2];
+
+    let root = parse_nodes(input.iter().cloned()).unwrap();
+
+    let sum = root.metadata_sum();
+    assert_eq!(sum, 138);
+}
+
+#[test]
+fn test_truncated() {
+    let input = &[2, 3, 0, 3, 10, 11, 12, 1, 1, 0, 1, 99, 2, 1];
+
+    assert_eq!(
+        PuzzleError::Truncated {
+            missing: "metadata"
+        },
+        parse_nodes(input.iter().cloned()).unwrap_err()
+    );
+}
+
+#[test]
+fn test_parse_error() {
+    let error = Day08::parse("2 3 0 3 10 11 12\n1 1 0 1 -99 2 1 1 2", &()).unwrap_err();
+
+    assert_eq!(
+        PuzzleError::Parse {
+            span: Span::new(2, 9, "-99")
+        },
+        error
+    );
+}
+
+#[test]
+fn test_read_error() {
+    let input = "2 3 0 3 10 11 12\n1 1 0 1 -99 2 1 1 2";
+    let error = Day08::read(input.as_bytes(), &()).unwrap_err();
+
+    assert!(error.to_string().starts_with("line 2, column 9"));
+    assert_eq!(
+        138,
+        Day08::read("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2".as_bytes(), &())
+            .unwrap()
+            .metadata_sum()
+    );
+}
+
+#[test]
+fn test_example_value() {
+    let input = &[2, 3, 0, 3, 10, 11, 12, 1, 1, 0, 1, 99, 2, 1, 1, 2];
+
+    let root = parse_nodes(input.iter().cloned()).unwrap();
+
+    let value = root.value();
+    assert_eq!(value, 66)
+}
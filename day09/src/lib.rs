@@ -0,0 +1,291 @@
+//! Day 9: Marble Mania
+//!
+//! Plays the elves' marble game on a circle implemented as doubly linked list
+//! in a `Vec`, to find the winning score.
+
+use aoc_common::cli::UsageError;
+use aoc_common::params::{positive, unknown_option};
+use aoc_common::{Params, Solution};
+use std::convert::Infallible;
+
+/// Solution of day 9 for the runners
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const INPUT: Option<&'static str> = None;
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = ();
+    type Params = Setup;
+    type Answer1 = Score;
+    type Answer2 = Score;
+    type Error = Infallible;
+
+    fn parse(_input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        Ok(())
+    }
+
+    fn part1(_input: &Self::Input, setup: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        let game = Game::new(setup.players, setup.last_marble);
+        let result = game.play();
+        Ok(result.into_iter().max().unwrap_or(0))
+    }
+
+    fn part2(_input: &Self::Input, setup: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let game = Game::new(setup.players, setup.last_marble * 100);
+        let result = game.play();
+        Ok(result.into_iter().max().unwrap_or(0))
+    }
+}
+
+/// The number of players and the points of the last marble, which are the puzzle input.
+///
+/// Part 2 is played until a marble worth 100 times as many points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Setup {
+    pub players: usize,
+    pub last_marble: usize,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Setup {
+            players: 459,
+            last_marble: 72_103,
+        }
+    }
+}
+
+impl Params for Setup {
+    const USAGE: &'static str = "[--players N] [--last-marble POINTS]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--players" => self.players = positive(name, value)?,
+            "--last-marble" => self.last_marble = positive(name, value)?,
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+pub type Score = usize;
+type MarbleId = usize;
+
+/// A marble in the circle, linked to its neighbours.
+#[derive(Clone, Debug)]
+pub struct Marble {
+    pub value: usize,
+    next: MarbleId,
+    prev: MarbleId,
+}
+
+/// Direction to move around the circle
+#[derive(Copy, Clone, Debug)]
+pub enum Direction {
+    Clockwise,
+    Counterclockwise,
+}
+
+/// A game of `players` taking turns, until `max_rounds` marbles are placed.
+#[derive(Clone, Debug)]
+pub struct Game {
+    circle: Circle,
+    players: Vec<Score>,
+    max_rounds: usize,
+
+    marble: usize,
+    current_player: usize,
+}
+
+impl Game {
+    /// A new game, with only the marble 0 in the circle.
+    pub fn new(players: usize, max_rounds: usize) -> Self {
+        Game {
+            circle: Circle::new(),
+            max_rounds,
+            players: vec![0; players],
+            current_player: 0,
+            marble: 0,
+        }
+    }
+
+    /// Play all rounds, and return the score of each player.
+    pub fn play(mut self) -> Vec<Score> {
+        for _round in 0..self.max_rounds {
+            self.advance();
+        }
+
+        self.players
+    }
+
+    fn advance(&mut self) {
+        self.marble += 1;
+        self.current_player = (self.current_player + 1) % self.players.len();
+        if self.marble.is_multiple_of(23) {
+            self.circle.step(Direction::Counterclockwise, 7);
+
+            let removed = self.circle.remove();
+            self.players[self.current_player] += self.marble + removed;
+        } else {
+            self.circle.step(Direction::Clockwise, 1);
+            self.circle.insert(self.marble);
+        }
+    }
+}
+
+/// A circle of marbles, with a current marble operations happen relative to.
+///
+/// ```
+/// use day09::{Circle, Direction};
+///
+/// let mut circle = Circle::new();
+/// circle.insert(1);
+/// circle.insert(2);
+/// circle.step(Direction::Counterclockwise, 2);
+/// assert_eq!(0, circle.current().value);
+///
+/// assert_eq!(0, circle.remove());
+/// assert_eq!(1, circle.current().value);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Circle {
+    marbles: Vec<Marble>,
+    current: MarbleId,
+}
+
+impl Default for Circle {
+    fn default() -> Self {
+        Circle::new()
+    }
+}
+
+impl Circle {
+    /// A circle containing only the marble 0.
+    pub fn new() -> Self {
+        Circle {
+            marbles: vec![Marble {
+                value: 0,
+                next: 0,
+                prev: 0,
+            }],
+            current: 0,
+        }
+    }
+    /// Move the current marble `steps` marbles into `direction`.
+    pub fn step(&mut self, direction: Direction, steps: usize) {
+        match direction {
+            Direction::Clockwise => {
+                for _ in 0..steps {
+                    self.current = self.marbles[self.current].next;
+                }
+            }
+            Direction::Counterclockwise => {
+                for _ in 0..steps {
+                    self.current = self.marbles[self.current].prev;
+                }
+            }
+        }
+    }
+
+    /// The current marble.
+    pub fn current(&self) -> &Marble {
+        &self.marbles[self.current]
+    }
+
+    fn mut_next(&mut self) -> &mut Marble {
+        let next;
+        {
+            let current = self.current();
+            next = current.next;
+        }
+        &mut self.marbles[next]
+    }
+
+    fn mut_prev(&mut self) -> &mut Marble {
+        let prev;
+        {
+            let current = self.current();
+            prev = current.prev;
+        }
+        &mut self.marbles[prev]
+    }
+
+    /// Insert a marble clockwise after the current marble, and make it the current one.
+    pub fn insert(&mut self, value: usize) {
+        let new;
+        {
+            let current = self.current();
+            new = Marble {
+                value,
+                prev: self.current,
+                next: current.next,
+            };
+        }
+
+        self.marbles.push(new);
+        let marble_id = self.marbles.len() - 1;
+        self.current = marble_id;
+
+        let prev = self.mut_prev();
+        prev.next = marble_id;
+
+        let next = self.mut_next();
+        next.prev = marble_id;
+    }
+
+    /// Remove the current marble, and return its value.
+    /// The marble clockwise of the removed one becomes the current one.
+    pub fn remove(&mut self) -> usize {
+        let prev_id;
+        let next_id;
+        let value;
+        {
+            let current = self.current();
+            prev_id = current.prev;
+            next_id = current.next;
+            value = current.value;
+        }
+        {
+            let next = self.mut_next();
+            next.prev = prev_id;
+        }
+        {
+            let prev = self.mut_prev();
+            prev.next = next_id;
+        }
+        self.current = next_id;
+
+        value
+    }
+}
+
+#[test]
+fn test_game() {
+    let game = Game::new(9, 25);
+    let result = game.play();
+
+    let high_score = result.iter().max().unwrap();
+
+    assert_eq!(32, *high_score);
+}
+
+#[test]
+fn test_games() {
+    let games = &[
+        (10, 1618, 8317),
+        (13, 7999, 146373),
+        (17, 1104, 2764),
+        (21, 6111, 54718),
+        (30, 5807, 37305),
+    ];
+
+    for (players, limit, expected) in games {
+        let game = Game::new(*players, *limit);
+        let result = game.play();
+
+        let high_score = result.iter().max().unwrap();
+        assert_eq!(high_score, expected);
+    }
+}
@@ -0,0 +1,497 @@
+//! Day 10: The Stars Align
+//!
+//! Moves the points of light until they are closest together,
+//! which is when they spell out the message.
+
+use aoc_common::diagnostic::{parse_lines, Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::input::{read_lines, StreamError};
+use aoc_common::random::Rng;
+use aoc_common::render::{Color, Render};
+use aoc_common::{Answer, Grid2D, Json, Solution};
+use regex::Regex;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+#[macro_use]
+extern crate lazy_static;
+
+/// Solution of day 10 for the runners
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day10.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Points;
+    type Params = ();
+    type Answer1 = Message;
+    type Answer2 = usize;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        let point_list: Result<Vec<Point>, _> = parse_lines(input, FromStr::from_str).collect();
+        Ok(Points::new(point_list?))
+    }
+
+    fn read<R: BufRead>(
+        reader: R,
+        _params: &Self::Params,
+    ) -> Result<Self::Input, StreamError<Self::Error>> {
+        let point_list: Result<Vec<Point>, _> = read_points(reader).collect();
+        Ok(Points::new(point_list?))
+    }
+
+    fn part1(points: &Self::Input, _params: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        let (points, seconds) = converge(points)?;
+        Ok(Message { points, seconds })
+    }
+
+    fn part2(points: &Self::Input, _params: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let (_message, seconds) = converge(points)?;
+        Ok(seconds)
+    }
+}
+
+// Letters 5 lights wide and 6 high, rows separated by '/'
+const FONT: [&str; 26] = [
+    ".###./#...#/#...#/#####/#...#/#...#", // A
+    "####./#...#/####./#...#/#...#/####.", // B
+    ".###./#...#/#..../#..../#...#/.###.", // C
+    "####./#...#/#...#/#...#/#...#/####.", // D
+    "#####/#..../####./#..../#..../#####", // E
+    "#####/#..../####./#..../#..../#....", // F
+    ".###./#..../#..../#..##/#...#/.####", // G
+    "#...#/#...#/#####/#...#/#...#/#...#", // H
+    "#####/..#../..#../..#../..#../#####", // I
+    "..###/...#./...#./...#./#..#./.##..", // J
+    "#...#/#..#./###../#..#./#...#/#...#", // K
+    "#..../#..../#..../#..../#..../#####", // L
+    "#...#/##.##/#.#.#/#...#/#...#/#...#", // M
+    "#...#/##..#/#.#.#/#..##/#...#/#...#", // N
+    ".###./#...#/#...#/#...#/#...#/.###.", // O
+    "####./#...#/#...#/####./#..../#....", // P
+    ".###./#...#/#...#/#.#.#/#..#./.##.#", // Q
+    "####./#...#/#...#/####./#..#./#...#", // R
+    ".####/#..../.###./....#/....#/####.", // S
+    "#####/..#../..#../..#../..#../..#..", // T
+    "#...#/#...#/#...#/#...#/#...#/.###.", // U
+    "#...#/#...#/#...#/#...#/.#.#./..#..", // V
+    "#...#/#...#/#...#/#.#.#/##.##/#...#", // W
+    "#...#/.#.#./..#../..#../.#.#./#...#", // X
+    "#...#/.#.#./..#../..#../..#../..#..", // Y
+    "#####/...#./..#../.#.../#..../#####", // Z
+];
+
+impl Generate for Day10 {
+    const SIZE: usize = 8;
+
+    /// Points of light spelling out a message of `size` random letters
+    /// after 10000 to 11000 seconds, with velocities of up to 5 in each direction.
+    ///
+    /// On each edge of the message two points move apart, so the points are
+    /// closest together exactly when they spell out the message.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let seconds = rng.range(10_000..=11_000);
+        // Displaying the message needs positive coordinates
+        let (left, top) = (rng.range(100..=200), rng.range(100..=200));
+
+        let mut lights = Vec::new();
+        for position in 0..size as i64 {
+            let letter = rng.choose(&FONT);
+            for (y, row) in letter.split('/').enumerate() {
+                for (x, light) in row.chars().enumerate() {
+                    if light == '#' {
+                        lights.push((left + 7 * position + x as i64, top + y as i64));
+                    }
+                }
+            }
+        }
+
+        let speed = |rng: &mut Rng| rng.range(1..=5) as i32;
+        let velocity = |rng: &mut Rng| {
+            let sign = |rng: &mut Rng| if rng.chance(1, 2) { 1 } else { -1 };
+            (sign(rng) * speed(rng), sign(rng) * speed(rng))
+        };
+        let mut points: Vec<_> = lights.iter().map(|light| (*light, velocity(rng))).collect();
+
+        let edges = [
+            lights.iter().min_by_key(|(x, _)| *x),
+            lights.iter().max_by_key(|(x, _)| *x),
+            lights.iter().min_by_key(|(_, y)| *y),
+            lights.iter().max_by_key(|(_, y)| *y),
+        ];
+        for (edge, light) in edges.iter().enumerate() {
+            let light = *light.expect("The font has no empty letters");
+            for direction in &[1, -1] {
+                let (mut vel_x, mut vel_y) = velocity(rng);
+                if edge < 2 {
+                    vel_x = direction * vel_x.abs();
+                } else {
+                    vel_y = direction * vel_y.abs();
+                }
+                points.push((light, (vel_x, vel_y)));
+            }
+        }
+        rng.shuffle(&mut points);
+
+        points
+            .iter()
+            .map(|((x, y), (vel_x, vel_y))| {
+                let point = Point {
+                    x: x - i64::from(*vel_x) * seconds,
+                    y: y - i64::from(*vel_y) * seconds,
+                    vel_x: *vel_x,
+                    vel_y: *vel_y,
+                };
+                format!("{}\n", point)
+            })
+            .collect()
+    }
+}
+
+/// Everything that can go wrong on day 10
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A line is not a point of light
+    Parse { span: Span, expected: &'static str },
+    /// There are no points of light in the sky
+    NoPoints,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span, expected } => {
+                write!(f, "{}: expected {}, found '{}'", span, expected, span.text)
+            }
+            PuzzleError::NoPoints => write!(f, "There are no points of light"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            PuzzleError::NoPoints => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            PuzzleError::NoPoints => None,
+        }
+    }
+}
+
+/// The points of light spelling out the message, after `seconds`.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub points: Points,
+    pub seconds: usize,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.points)
+    }
+}
+
+impl Answer for Message {
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        let dimensions = self
+            .points
+            .dimensions()
+            .expect("Converged points are not empty");
+        vec![
+            ("seconds", Json::from(self.seconds)),
+            ("width", Json::from(1 + dimensions.max_x - dimensions.min_x)),
+            (
+                "height",
+                Json::from(1 + dimensions.max_y - dimensions.min_y),
+            ),
+        ]
+    }
+}
+
+impl Render for Day10 {
+    /// The star field at the moment the points spell out the message.
+    fn render(points: &Self::Input, _params: &Self::Params) -> Result<Grid2D<Color>, Self::Error> {
+        let (message, _) = converge(points)?;
+        let sky = message.sky()?;
+        Ok(sky.map(|lit| {
+            if *lit {
+                Color::rgb(255, 240, 180)
+            } else {
+                Color::rgb(10, 15, 40)
+            }
+        }))
+    }
+}
+
+/// Move the points until they are closest together, which is when they form the message.
+/// Returns the points at that moment, and the number of seconds it took.
+pub fn converge(points: &Points) -> Result<(Points, usize), PuzzleError> {
+    let mut points = points.clone();
+    let mut i = 0;
+    let mut last_size = i64::MAX;
+    loop {
+        points.step();
+        let dimensions = points.dimensions()?;
+        let current_size =
+            (dimensions.max_x - dimensions.min_x) + (dimensions.max_y - dimensions.min_y);
+        if current_size > last_size {
+            break;
+        }
+        i += 1;
+        last_size = current_size;
+    }
+    points.step_back();
+    Ok((points, i))
+}
+
+/// All points of light, displayed as the sky they light up.
+#[derive(Debug, Clone)]
+pub struct Points(Vec<Point>);
+
+/// The bounding box of all points, inclusive.
+#[derive(Debug, Clone)]
+pub struct Dimensions {
+    pub min_x: i64,
+    pub max_x: i64,
+    pub min_y: i64,
+    pub max_y: i64,
+}
+
+impl Points {
+    /// The points at their initial position.
+    pub fn new(points: Vec<Point>) -> Points {
+        Points(points)
+    }
+
+    /// Fails if there are no points.
+    pub fn dimensions(&self) -> Result<Dimensions, PuzzleError> {
+        let points = &self.0;
+        let max_x = points
+            .iter()
+            .max_by_key(|p| p.x)
+            .map(|p| p.x)
+            .ok_or(PuzzleError::NoPoints)?;
+        let min_x = points
+            .iter()
+            .min_by_key(|p| p.x)
+            .map(|p| p.x)
+            .ok_or(PuzzleError::NoPoints)?;
+        let max_y = points
+            .iter()
+            .max_by_key(|p| p.y)
+            .map(|p| p.y)
+            .ok_or(PuzzleError::NoPoints)?;
+        let min_y = points
+            .iter()
+            .min_by_key(|p| p.y)
+            .map(|p| p.y)
+            .ok_or(PuzzleError::NoPoints)?;
+
+        Ok(Dimensions {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+        })
+    }
+
+    /// Move all points by their velocity.
+    pub fn step(&mut self) {
+        for point in &mut self.0 {
+            point.x += i64::from(point.vel_x);
+            point.y += i64::from(point.vel_y);
+        }
+    }
+
+    /// Move all points back by their velocity.
+    pub fn step_back(&mut self) {
+        for point in &mut self.0 {
+            point.x -= i64::from(point.vel_x);
+            point.y -= i64::from(point.vel_y);
+        }
+    }
+
+    /// The bounding box of all points, with the cells lit by a point.
+    pub fn sky(&self) -> Result<Grid2D<bool>, PuzzleError> {
+        let dimensions = self.dimensions()?;
+
+        // len(), is max_idx + 1
+        let len_x = 1 + dimensions.max_x - dimensions.min_x;
+        let len_y = 1 + dimensions.max_y - dimensions.min_y;
+
+        let mut sky = Grid2D::with_origin(
+            dimensions.min_x,
+            dimensions.min_y,
+            len_x as usize,
+            len_y as usize,
+            false,
+        );
+        for point in &self.0 {
+            sky[(point.x, point.y)] = true;
+        }
+        Ok(sky)
+    }
+}
+
+impl fmt::Display for Points {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let sky = self.sky().map_err(|_| fmt::Error)?;
+
+        // █  for Value, • for no value, each row on a new line
+        let rows = sky.render(" ", |lit| if *lit { '█' } else { '•' });
+        write!(f, "\n{}", rows)
+    }
+}
+
+/// The points of light, parsed one line at a time while reading them.
+pub fn read_points<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<Point, StreamError<PuzzleError>>> {
+    read_lines(reader, FromStr::from_str)
+}
+
+/// A point of light with its position and velocity per second.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+    pub vel_x: i32,
+    pub vel_y: i32,
+}
+
+lazy_static! {
+    static ref PAIR_RX: Regex = {
+        Regex::new(r"position=<\s*(?P<x>[-0-9]+)\s*,\s*(?P<y>[-0-9]+)\s*> velocity=<\s*(?P<vel_x>[-0-9]+)\s*,\s*(?P<vel_y>[-0-9]+)\s*>").unwrap()
+    };
+}
+
+impl FromStr for Point {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let caps = PAIR_RX.captures(s).ok_or_else(|| PuzzleError::Parse {
+            span: Span::locate(s, s),
+            expected: "a point like 'position=< 9,  1> velocity=< 0,  2>'",
+        })?;
+        // all groups are mandatory, but the pattern allows any sequence of digits and signs
+        let capture = |name| caps.name(name).expect("Point matched").as_str();
+        let number_error = |text| PuzzleError::Parse {
+            span: Span::locate(s, text),
+            expected: "a number",
+        };
+
+        let x = capture("x");
+        let x = x.parse().map_err(|_| number_error(x))?;
+        let y = capture("y");
+        let y = y.parse().map_err(|_| number_error(y))?;
+        let vel_x = capture("vel_x");
+        let vel_x = vel_x.parse().map_err(|_| number_error(vel_x))?;
+        let vel_y = capture("vel_y");
+        let vel_y = vel_y.parse().map_err(|_| number_error(vel_y))?;
+
+        Ok(Point { x, y, vel_x, vel_y })
+    }
+}
+
+/// The point as in the puzzle input, e.g. `position=< 9,  1> velocity=< 0,  2>`
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "position=<{:6}, {:6}> velocity=<{:2}, {:2}>",
+            self.x, self.y, self.vel_x, self.vel_y
+        )
+    }
+}
+
+#[test]
+fn test_generate_message() {
+    let input = Day10::generate(&mut Rng::new(3), 4);
+    let points = Day10::parse(&input, &()).unwrap();
+    let (message, seconds) = converge(&points).unwrap();
+    let dimensions = message.dimensions().unwrap();
+
+    assert!((10_000..=11_000).contains(&seconds));
+    assert_eq!(4 * 7 - 2, 1 + dimensions.max_x - dimensions.min_x);
+    assert_eq!(6, 1 + dimensions.max_y - dimensions.min_y);
+}
+
+#[test]
+fn test_display_negative_positions() {
+    let input = "position=<-2, -1> velocity=< 0,  0>\nposition=< 0,  0> velocity=< 0,  0>";
+    let points = Day10::parse(input, &()).unwrap();
+
+    assert_eq!("\n█ • •\n• • █", points.to_string());
+}
+
+#[test]
+fn test_point_round_trip() {
+    aoc_common::check::round_trip(|choices| Point {
+        x: choices.range(i64::MIN..=i64::MAX),
+        y: choices.range(-100_000..=100_000),
+        vel_x: choices.range(i64::from(i32::MIN)..=i64::from(i32::MAX)) as i32,
+        vel_y: choices.range(-5..=5) as i32,
+    });
+}
+
+#[test]
+fn test_parse_point_error() {
+    let input = "position=< 9,  1> velocity=< 0,  2>\nposition=< 7,  0> velocity=<1-,  0>";
+
+    let points: Result<Vec<Point>, _> = parse_lines(input, FromStr::from_str).collect();
+
+    assert_eq!(
+        Err(PuzzleError::Parse {
+            span: Span::new(2, 29, "1-"),
+            expected: "a number",
+        }),
+        points
+    );
+}
+
+#[test]
+fn test_parse_point() {
+    let input = "position=< 9,  1> velocity=< 0,  2>\nposition=< 7,  0> velocity=<-1,  0>\nposition=< 3, -2> velocity=<-1,  1>";
+
+    let points: Result<Vec<Point>, _> = input.lines().map(FromStr::from_str).collect();
+
+    let points = points.unwrap();
+
+    assert_eq!(
+        points.as_slice(),
+        &[
+            Point {
+                x: 9,
+                y: 1,
+                vel_x: 0,
+                vel_y: 2
+            },
+            Point {
+                x: 7,
+                y: 0,
+                vel_x: -1,
+                vel_y: 0
+            },
+            Point {
+                x: 3,
+                y: -2,
+                vel_x: -1,
+                vel_y: 1,
+            },
+        ]
+    )
+}
@@ -0,0 +1,379 @@
+//! Day 11: Chronal Charge
+//!
+//! Finds the square of fuel cells with the largest total power in a grid,
+//! for a fixed size of 3x3 and for any size.
+
+use aoc_common::cli::UsageError;
+use aoc_common::grid::Coordinate;
+use aoc_common::params::{number, positive, unknown_option};
+use aoc_common::render::{Color, Render};
+use aoc_common::{Answer, Diagnostic, Grid2D, Json, Params, Solution};
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use std::error::Error;
+use std::fmt;
+
+/// Solution of day 11 for the runners
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const INPUT: Option<&'static str> = None;
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Grid;
+    type Params = FuelGrid;
+    type Answer1 = Origin;
+    type Answer2 = Square;
+    type Error = PuzzleError;
+
+    fn parse(_input: &str, fuel_grid: &Self::Params) -> Result<Self::Input, Self::Error> {
+        Ok(Grid::new(fuel_grid.serial, fuel_grid.size, fuel_grid.size))
+    }
+
+    fn part1(grid: &Self::Input, _params: &Self::Params) -> Result<Self::Answer1, Self::Error> {
+        // part 1 requests 3x3
+        let most_powerful_grid = grid
+            .all_subgrids_with_size(3, 3)?
+            .into_iter()
+            .max_by_key(|grid| grid.power_level_sum)
+            .ok_or(PuzzleError::NoSubGrid)?;
+        Ok(Origin(most_powerful_grid))
+    }
+
+    fn part2(grid: &Self::Input, fuel_grid: &Self::Params) -> Result<Self::Answer2, Self::Error> {
+        let most_powerful_subgrids = most_powerful_subgrid_for_square_sizes(grid, fuel_grid.size);
+        let most_powerful_grid = most_powerful_subgrids
+            .iter()
+            .max_by_key(|grid| grid.power_level_sum)
+            .ok_or(PuzzleError::NoSubGrid)?;
+        Ok(Square(*most_powerful_grid))
+    }
+}
+
+impl Render for Day11 {
+    /// A heat map of the power levels, from -5 in black to 4 in white.
+    fn render(grid: &Self::Input, _params: &Self::Params) -> Result<Grid2D<Color>, Self::Error> {
+        Ok(grid
+            .power_levels()
+            .map(|power| Color::heat(f64::from(power + 5) / 9.0)))
+    }
+}
+
+/// The serial number and the size of the square grid of fuel cells.
+///
+/// The serial number is the puzzle input, 2187 for the answers of this repository.
+/// The grid is at most `MAX_GRID_SIZE` cells wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuelGrid {
+    pub serial: GridSerial,
+    pub size: usize,
+}
+
+impl Default for FuelGrid {
+    fn default() -> Self {
+        FuelGrid {
+            serial: 2187,
+            size: 300,
+        }
+    }
+}
+
+/// The largest grid size accepted on the command line
+pub const MAX_GRID_SIZE: usize = 1_000;
+
+impl Params for FuelGrid {
+    const USAGE: &'static str = "[--serial N] [--grid-size CELLS]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--serial" => self.serial = number(name, value)?,
+            "--grid-size" => {
+                self.size = Some(positive(name, value)?)
+                    .filter(|size| *size <= MAX_GRID_SIZE)
+                    .ok_or_else(|| {
+                        UsageError::new(format!(
+                            "Option '{}' must be at most {}, not '{}'",
+                            name, MAX_GRID_SIZE, value
+                        ))
+                    })?
+            }
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+/// The most powerful subgrid of a fixed size, answered by its origin `x,y`.
+#[derive(Debug, Clone, Copy)]
+pub struct Origin(pub SubGrid);
+
+/// The most powerful square subgrid of any size, answered by `x,y,size`.
+#[derive(Debug, Clone, Copy)]
+pub struct Square(pub SubGrid);
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.0.origin_x, self.0.origin_y)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{}",
+            self.0.origin_x, self.0.origin_y, self.0.size_x
+        )
+    }
+}
+
+impl Answer for Origin {
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        self.0.details()
+    }
+}
+
+impl Answer for Square {
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        self.0.details()
+    }
+}
+
+impl SubGrid {
+    fn details(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("x", Json::from(self.origin_x)),
+            ("y", Json::from(self.origin_y)),
+            ("size", Json::from(self.size_x)),
+            ("power", Json::from(self.power_level_sum)),
+        ]
+    }
+}
+
+/// Everything that can go wrong on day 11
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A subgrid does not fit inside the grid
+    OutsideGrid {
+        from_x: usize,
+        from_y: usize,
+        size_x: usize,
+        size_y: usize,
+    },
+    /// The grid is too small to contain any subgrid of the requested size
+    NoSubGrid,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::OutsideGrid {
+                from_x,
+                from_y,
+                size_x,
+                size_y,
+            } => write!(
+                f,
+                "Subgrid of size {}x{} at offset {},{} must be fully inside grid",
+                size_x, size_y, from_x, from_y
+            ),
+            PuzzleError::NoSubGrid => write!(f, "Could not determine maximum subgrid power level"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {}
+
+/// The most powerful square subgrid for every size from 1 to `size`, starting with size 1.
+pub fn most_powerful_subgrid_for_square_sizes(grid: &Grid, size: usize) -> Vec<SubGrid> {
+    let bar = ProgressBar::new(size as u64);
+    let max_power_for_subgrid_size: Vec<_> = (1..size + 1)
+        .into_par_iter()
+        .map(|subgrid_size| {
+            let grids = grid
+                .all_subgrids_with_size(subgrid_size, subgrid_size)
+                .unwrap();
+
+            bar.inc(1);
+            *grids.iter().max_by_key(|g| g.power_level_sum).unwrap()
+        })
+        .collect();
+    bar.finish();
+    max_power_for_subgrid_size
+}
+
+pub type PowerLevel = i32;
+pub type GridSerial = i32;
+
+/// A rectangle of fuel cells, the origin is its top-left cell (1-based).
+#[derive(Copy, Clone, Debug)]
+pub struct SubGrid {
+    pub origin_x: usize,
+    pub origin_y: usize,
+    pub size_x: usize,
+    pub size_y: usize,
+    pub power_level_sum: PowerLevel,
+}
+
+/// The power level of the fuel cell at `x`,`y` (1-based).
+///
+/// Computed in 64 bits, which do not overflow for any serial number
+/// in grids of up to `MAX_GRID_SIZE` cells.
+pub fn power_level(x: usize, y: usize, grid_serial: GridSerial) -> PowerLevel {
+    // x plus 10
+    let rack_id = x as i64 + 10;
+    let y = y as i64;
+    // rack ID * y coordinate, then plus grid serial, then * rack ID
+    let power = rack_id * (i64::from(grid_serial) + rack_id * y);
+    // hundreds digit or zero
+    let power_level = if power > 100 { (power / 100) % 10 } else { 0 };
+
+    // final result minus 5
+    power_level as PowerLevel - 5
+}
+
+/// The fuel cells of a grid with the given serial number.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    // The power levels, at 0-based coordinates
+    cells: Grid2D<PowerLevel>,
+}
+
+impl Grid {
+    /// Compute the power level of all `size_x` by `size_y` fuel cells.
+    pub fn new(grid_serial: GridSerial, size_x: usize, size_y: usize) -> Self {
+        let cells = Grid2D::from_fn(size_x, size_y, |x, y| {
+            power_level(x as usize + 1, y as usize + 1, grid_serial)
+        });
+        Grid { cells }
+    }
+
+    /// The power level of each fuel cell, at 0-based coordinates.
+    pub fn power_levels(&self) -> &Grid2D<PowerLevel> {
+        &self.cells
+    }
+
+    /// The total power of a subgrid, where `from_x`,`from_y` are 0-based offsets.
+    pub fn sub_grid(
+        &self,
+        from_x: usize,
+        from_y: usize,
+        size_x: usize,
+        size_y: usize,
+    ) -> Result<SubGrid, PuzzleError> {
+        let view = self
+            .cells
+            .view(from_x as Coordinate, from_y as Coordinate, size_x, size_y)
+            .ok_or(PuzzleError::OutsideGrid {
+                from_x,
+                from_y,
+                size_x,
+                size_y,
+            })?;
+
+        let power_level_sum = view.rows().map(|row| row.iter().sum::<PowerLevel>()).sum();
+        Ok(SubGrid {
+            origin_x: from_x + 1,
+            origin_y: from_y + 1,
+            size_x,
+            size_y,
+            power_level_sum,
+        })
+    }
+
+    /// The total power of all subgrids of the given size.
+    pub fn all_subgrids_with_size(
+        &self,
+        size_x: usize,
+        size_y: usize,
+    ) -> Result<Vec<SubGrid>, PuzzleError> {
+        // A grid smaller than the subgrid may be given on the command line
+        let max_y = self
+            .cells
+            .height()
+            .checked_sub(size_y)
+            .ok_or(PuzzleError::NoSubGrid)?;
+        let max_x = self
+            .cells
+            .width()
+            .checked_sub(size_x)
+            .ok_or(PuzzleError::NoSubGrid)?;
+
+        let mut subgrids: Vec<SubGrid> = Vec::new();
+        for y in 0..=max_y {
+            for x in 0..=max_x {
+                subgrids.push(self.sub_grid(x, y, size_x, size_y)?);
+            }
+        }
+        Ok(subgrids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // This is the puzzle input
+    const TEST_SERIAL: GridSerial = 8;
+
+    #[test]
+    fn test_power_level() {
+        assert_eq!(4, power_level(3, 5, TEST_SERIAL));
+    }
+
+    #[test]
+    fn test_power_level_examples() {
+        let tests: &[(usize, usize, GridSerial, PowerLevel)] =
+            &[(122, 79, 57, -5), (217, 196, 39, 0), (101, 153, 71, 4)];
+
+        for (x, y, grid_serial, expected) in tests.iter() {
+            assert_eq!(*expected, power_level(*x, *y, *grid_serial))
+        }
+    }
+
+    #[test]
+    fn test_extreme_params() {
+        let max = MAX_GRID_SIZE;
+        assert_eq!(-5, power_level(max, max, GridSerial::MIN));
+        assert!((-5..=4).contains(&power_level(max, max, GridSerial::MAX)));
+        assert!(FuelGrid::from_options(&[(
+            "--grid-size".to_owned(),
+            (MAX_GRID_SIZE + 1).to_string()
+        )])
+        .is_err());
+        assert_eq!(
+            Ok(FuelGrid {
+                serial: GridSerial::MAX,
+                size: MAX_GRID_SIZE
+            }),
+            FuelGrid::from_options(&[
+                ("--serial".to_owned(), GridSerial::MAX.to_string()),
+                ("--grid-size".to_owned(), MAX_GRID_SIZE.to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_small_grid() {
+        let fuel_grid = FuelGrid {
+            serial: TEST_SERIAL,
+            size: 3,
+        };
+        let grid = Day11::parse("", &fuel_grid).unwrap();
+        let sum = grid.sub_grid(0, 0, 3, 3).unwrap().power_level_sum;
+
+        assert_eq!(1, grid.all_subgrids_with_size(3, 3).unwrap().len());
+        assert_eq!(4, grid.all_subgrids_with_size(2, 2).unwrap().len());
+        assert_eq!("1,1", Day11::part1(&grid, &fuel_grid).unwrap().to_string());
+        let sizes = most_powerful_subgrid_for_square_sizes(&grid, 3);
+        assert_eq!(
+            vec![1, 2, 3],
+            sizes.iter().map(|g| g.size_x).collect::<Vec<_>>()
+        );
+        assert_eq!(sum, sizes[2].power_level_sum);
+        assert!(Day11::part2(&grid, &fuel_grid).is_ok());
+    }
+}
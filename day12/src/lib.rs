@@ -0,0 +1,526 @@
+//! Day 12: Subterranean Sustainability
+//!
+//! Grows plants in an infinite row of pots, following rules based on the
+//! neighbourhood of each pot.
+
+use aoc_common::cli::UsageError;
+use aoc_common::diagnostic::{Diagnostic, Span};
+use aoc_common::generate::Generate;
+use aoc_common::params::{number, positive, unknown_option};
+use aoc_common::random::Rng;
+use aoc_common::render::{Color, Render};
+use aoc_common::{Grid2D, Params, Solution};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Solution of day 12 for the runners
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    const INPUT: Option<&'static str> =
+        Some(concat!(env!("CARGO_MANIFEST_DIR"), "/input/day12.txt"));
+    const ANSWERS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+    type Input = Notes;
+    type Params = Generations;
+    type Answer1 = i64;
+    type Answer2 = i64;
+    type Error = PuzzleError;
+
+    fn parse(input: &str, _params: &Self::Params) -> Result<Self::Input, Self::Error> {
+        // parse initial config
+        let mut lines = input.lines().enumerate();
+        let initial_config = lines
+            .next()
+            .map(|(_, line)| line.chars().filter_map(char_as_pot).collect::<Vec<_>>())
+            .ok_or(PuzzleError::NoInitialState)?;
+
+        // parse rules
+        let rules: Vec<Rule> = lines
+            .filter(|(_, l)| !l.is_empty())
+            .map(|(index, line)| Rule::from_str(line).map_err(|error| error.on_line(index)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Notes {
+            initial_config,
+            rules,
+        })
+    }
+
+    fn part1(
+        notes: &Self::Input,
+        generations: &Self::Params,
+    ) -> Result<Self::Answer1, Self::Error> {
+        let mut pots = Pots::new(notes.initial_config.clone());
+
+        for _i in 0..generations.part1 {
+            pots.next_generation(&notes.rules);
+        }
+        Ok(pots.sum_full_pot_numbers())
+    }
+
+    fn part2(
+        notes: &Self::Input,
+        generations: &Self::Params,
+    ) -> Result<Self::Answer2, Self::Error> {
+        let rules = &notes.rules;
+
+        // Admittedly not the prettiest solution
+        // Searching for a constant difference between rounds over an extended number of rounds to gain some confidence.
+        let mut pots = Pots::new(notes.initial_config.clone());
+        let mut previous_sum = 0;
+        let mut constant_rate = 0;
+        let mut consistency = 0;
+        loop {
+            // Few enough generations to simply grow all of them
+            if pots.generation as i64 >= generations.part2 {
+                return Ok(pots.sum_full_pot_numbers());
+            }
+            pots.next_generation(rules);
+
+            let current_sum = pots.sum_full_pot_numbers();
+            let rate = current_sum - previous_sum;
+
+            if rate != constant_rate {
+                consistency = 0;
+            } else {
+                // If the rate didn't change for 1000 rounds I assume that it won't do so anymore
+                if consistency >= 1000 {
+                    break;
+                } else {
+                    consistency += 1
+                }
+            }
+
+            previous_sum = current_sum;
+            constant_rate = rate;
+        }
+
+        // we extrapolate the value by adding the known sum to the constant rate multiplied by the remaining generations
+        Ok(pots.sum_full_pot_numbers()
+            + constant_rate * (generations.part2 - pots.generation as i64))
+    }
+}
+
+/// The number of generations to grow, 20 for part 1 and 50 billion for part 2 in the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Generations {
+    pub part1: usize,
+    pub part2: i64,
+}
+
+impl Default for Generations {
+    fn default() -> Self {
+        Generations {
+            part1: 20,
+            part2: 50_000_000_000,
+        }
+    }
+}
+
+impl Params for Generations {
+    const USAGE: &'static str = "[--generations N] [--target-generations N]";
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), UsageError> {
+        match name {
+            "--generations" => self.part1 = number(name, value)?,
+            "--target-generations" => self.part2 = positive(name, value)?,
+            _ => return Err(unknown_option::<Self>(name)),
+        }
+        Ok(())
+    }
+}
+
+impl Generate for Day12 {
+    const SIZE: usize = 100;
+
+    /// An initial state of `size` random pots, and a rule for each of the 32 patterns.
+    ///
+    /// Part 2 relies on the plants settling into a pattern which moves at a constant speed,
+    /// so random rules are drawn until they do so within 1000 generations.
+    /// Empty pots always stay empty, otherwise the row would fill up.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let random_pot = |rng: &mut Rng| {
+            if rng.chance(1, 2) {
+                Pot::Full
+            } else {
+                Pot::Empty
+            }
+        };
+        loop {
+            let initial_config: Vec<_> = (0..size).map(|_| random_pot(rng)).collect();
+            let rules: Vec<_> = (0..32)
+                .map(|bits| {
+                    let mut pattern = [Pot::Empty; 5];
+                    for (i, pot) in pattern.iter_mut().enumerate() {
+                        if bits & (1 << (4 - i)) != 0 {
+                            *pot = Pot::Full;
+                        }
+                    }
+                    let next = if bits == 0 {
+                        Pot::Empty
+                    } else {
+                        random_pot(rng)
+                    };
+                    Rule { pattern, next }
+                })
+                .collect();
+
+            if settles(&initial_config, &rules) {
+                let notes = Notes {
+                    initial_config,
+                    rules,
+                };
+                return notes.to_string();
+            }
+        }
+    }
+}
+
+// Whether the plants keep their pattern from one generation to the next within 1000 generations,
+// giving up early if they die out or spread too far
+fn settles(initial_config: &[Pot], rules: &[Rule]) -> bool {
+    let mut pots = Pots::new(initial_config.to_vec());
+    let mut previous = pots.plants().to_vec();
+    for _ in 0..1000 {
+        pots.next_generation(rules);
+        let plants = pots.plants();
+        if plants.is_empty() || plants.len() > 2 * initial_config.len() + 100 {
+            return false;
+        }
+        if plants == previous.as_slice() {
+            return true;
+        }
+        previous = plants.to_vec();
+    }
+    false
+}
+
+impl Render for Day12 {
+    /// The history of the pots, a row per generation of part 1 from the initial state on,
+    /// with plants in green and the column of pot 0 highlighted.
+    fn render(
+        notes: &Self::Input,
+        generations: &Self::Params,
+    ) -> Result<Grid2D<Color>, Self::Error> {
+        let mut pots = Pots::new(notes.initial_config.clone());
+        let mut history = vec![pots.full_pot_numbers().collect::<Vec<_>>()];
+        for _ in 0..generations.part1 {
+            pots.next_generation(&notes.rules);
+            history.push(pots.full_pot_numbers().collect());
+        }
+
+        let numbers = || history.iter().flatten().cloned();
+        let leftmost = numbers().min().unwrap_or(0).min(0);
+        let rightmost = numbers().max().unwrap_or(0).max(0);
+        let mut image = Grid2D::with_origin(
+            leftmost,
+            0,
+            (1 + rightmost - leftmost) as usize,
+            history.len(),
+            Color::rgb(35, 25, 20),
+        );
+        for ((number, _), color) in image.iter_mut() {
+            if number == 0 {
+                *color = Color::rgb(70, 55, 45);
+            }
+        }
+        for (generation, plants) in (0..).zip(&history) {
+            for number in plants {
+                image[(*number, generation)] = Color::rgb(60, 180, 75);
+            }
+        }
+        Ok(image)
+    }
+}
+
+/// Everything that can go wrong on day 12
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A line is not a rule
+    Parse { span: Span, expected: &'static str },
+    /// The input is empty
+    NoInitialState,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Parse { span, expected } => {
+                write!(f, "{}: expected {}, found '{}'", span, expected, span.text)
+            }
+            PuzzleError::NoInitialState => write!(f, "Could not get initial config"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+impl Diagnostic for PuzzleError {
+    fn span(&self) -> Option<&Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            PuzzleError::NoInitialState => None,
+        }
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            PuzzleError::Parse { span, .. } => Some(span),
+            PuzzleError::NoInitialState => None,
+        }
+    }
+}
+
+/// The puzzle input: the initial state of the pots and the rules for the next generation
+#[derive(Debug, Clone)]
+pub struct Notes {
+    pub initial_config: Vec<Pot>,
+    pub rules: Vec<Rule>,
+}
+
+/// The notes in the format of the puzzle input.
+impl fmt::Display for Notes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "initial state: ")?;
+        write_pots(f, &self.initial_config)?;
+        writeln!(f, "\n")?;
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_pots(f: &mut fmt::Formatter, pots: &[Pot]) -> fmt::Result {
+    for pot in pots {
+        match pot {
+            Pot::Empty => write!(f, ".")?,
+            Pot::Full => write!(f, "#")?,
+        }
+    }
+    Ok(())
+}
+
+/// A pot either contains a plant or not.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Pot {
+    Empty,
+    Full,
+}
+
+/// A generation of the row of pots.
+/// All pots left and right of the stored ones are empty.
+#[derive(Debug, Clone)]
+pub struct Pots {
+    leftmost_number: i64,
+    generation: usize,
+    pots: Vec<Pot>,
+}
+
+const ALIGNMENT: usize = 4;
+
+impl Pots {
+    /// The pots of generation 0, with the first one numbered 0.
+    pub fn new(pots: Vec<Pot>) -> Self {
+        let mut pots = Pots {
+            leftmost_number: 0,
+            generation: 0,
+            pots,
+        };
+
+        pots.align();
+        pots
+    }
+
+    /// The current generation
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The pots from the first to the last one containing a plant, empty if there are none.
+    pub fn plants(&self) -> &[Pot] {
+        let first = self.pots.iter().position(|p| *p == Pot::Full);
+        let last = self.pots.iter().rposition(|p| *p == Pot::Full);
+        match (first, last) {
+            (Some(first), Some(last)) => &self.pots[first..=last],
+            _ => &[],
+        }
+    }
+
+    /// Sum of the numbers of all pots containing a plant.
+    pub fn sum_full_pot_numbers(&self) -> i64 {
+        self.full_pot_numbers().sum()
+    }
+
+    /// The numbers of all pots containing a plant, from left to right.
+    pub fn full_pot_numbers(&self) -> impl Iterator<Item = i64> + '_ {
+        self.pots
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| **p == Pot::Full)
+            .map(move |(i, _)| self.leftmost_number + i as i64)
+    }
+
+    // the pattern ....# and #.... must be able to be matched,
+    // so we need at least 4 empty Pots at each side
+    // also we need at least 5 pots to be able to calculate something useful
+    fn align(&mut self) {
+        while self.pots.len() < 5 {
+            self.pots.push(Pot::Empty)
+        }
+        while self.pots[0..ALIGNMENT].contains(&Pot::Full) {
+            self.leftmost_number -= 1;
+            self.pots.insert(0, Pot::Empty)
+        }
+        while self.pots[self.pots.len() - ALIGNMENT..self.pots.len()].contains(&Pot::Full) {
+            self.pots.push(Pot::Empty)
+        }
+    }
+
+    /// Apply the rules to all pots at once.
+    pub fn next_generation(&mut self, rules: &[Rule]) {
+        let old = self.pots.clone();
+        for i in 2..old.len() - 2 {
+            let pattern = &old[i - 2..=i + 2];
+            for rule in rules {
+                if pattern == rule.pattern {
+                    self.pots[i] = rule.next
+                }
+            }
+        }
+        self.align();
+        self.generation += 1
+    }
+}
+
+impl fmt::Display for Pots {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}: ", self.generation)?;
+        write_pots(f, &self.pots)
+    }
+}
+
+/// A pattern of 5 Pots describes the state of the middle pot in the next generation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub pattern: [Pot; 5],
+    pub next: Pot,
+}
+
+// Format:
+// ..### => #
+impl FromStr for Rule {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let parse_error = |text, expected| PuzzleError::Parse {
+            span: Span::locate(s, text),
+            expected,
+        };
+        let (pattern_text, next_text) = s
+            .split_once("=>")
+            .ok_or_else(|| parse_error(s, "a rule like '..### => #'"))?;
+        let (pattern_text, next_text) = (pattern_text.trim(), next_text.trim());
+
+        let mut pattern = [Pot::Empty; 5];
+        let mut chars = pattern_text.char_indices();
+        for pot in &mut pattern {
+            let (idx, c) = chars
+                .next()
+                .ok_or_else(|| parse_error(pattern_text, "a pattern of 5 pots"))?;
+            *pot = char_as_pot(c)
+                .ok_or_else(|| parse_error(&pattern_text[idx..idx + c.len_utf8()], "a pot"))?;
+        }
+        if chars.next().is_some() {
+            return Err(parse_error(pattern_text, "a pattern of 5 pots"));
+        }
+
+        let mut chars = next_text.chars();
+        let next = match (chars.next().and_then(char_as_pot), chars.next()) {
+            (Some(next), None) => next,
+            _ => return Err(parse_error(next_text, "a single pot")),
+        };
+
+        Ok(Rule { pattern, next })
+    }
+}
+
+/// The rule in the format of the puzzle input, e.g. `..### => #`
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_pots(f, &self.pattern)?;
+        write!(f, " => ")?;
+        write_pots(f, &[self.next])
+    }
+}
+
+fn char_as_pot(c: char) -> Option<Pot> {
+    match c {
+        '#' => Some(Pot::Full),
+        '.' => Some(Pot::Empty),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_rule_round_trip() {
+    aoc_common::check::round_trip(|choices| {
+        let mut pattern = [Pot::Empty; 5];
+        for pot in &mut pattern {
+            *pot = *choices.choose(&[Pot::Empty, Pot::Full]);
+        }
+        Rule {
+            pattern,
+            next: *choices.choose(&[Pot::Empty, Pot::Full]),
+        }
+    });
+}
+
+#[test]
+fn test_parse_pot() {
+    assert_eq!(Pot::Empty, char_as_pot('.').unwrap());
+    assert_eq!(Pot::Full, char_as_pot('#').unwrap());
+
+    assert!(char_as_pot(' ').is_none());
+    assert!(char_as_pot('=').is_none());
+    assert!(char_as_pot('>').is_none());
+}
+
+#[test]
+fn test_parse_rule() {
+    let rule: Rule = FromStr::from_str("..### => #").unwrap();
+    assert_eq!(
+        Rule {
+            pattern: [Pot::Empty, Pot::Empty, Pot::Full, Pot::Full, Pot::Full],
+            next: Pot::Full
+        },
+        rule
+    )
+}
+
+#[test]
+fn test_parse_rule_error() {
+    let input = "initial state: #..#.#..##......###...###\n\n...## => #\n..#.. =>";
+
+    let error = Day12::parse(input, &Generations::default()).unwrap_err();
+
+    assert_eq!(
+        PuzzleError::Parse {
+            span: Span::new(4, 9, ""),
+            expected: "a single pot",
+        },
+        error
+    );
+}
+
+#[test]
+fn test_align_extend() {
+    let pots = vec![Pot::Full];
+    let pots = Pots::new(pots);
+
+    assert_eq!(pots.leftmost_number, -4);
+    assert_eq!(pots.pots.len(), 9)
+}